@@ -0,0 +1,90 @@
+use crate::{DateDataSource, Element, Scale};
+use chrono::{DateTime, NaiveDate, TimeZone};
+use std::collections::BTreeMap;
+use std::marker::PhantomData;
+
+/// How the values of the events of the same day are combined.
+#[derive(Clone, Copy)]
+pub enum Reducer {
+    /// Number of events, the values are ignored.
+    Count,
+    Sum,
+    Min,
+    Max,
+}
+
+impl Reducer {
+    fn reduce(&self, current: Option<f64>, value: f64) -> f64 {
+        match (self, current) {
+            (Reducer::Count, c) => c.unwrap_or(0.0) + 1.0,
+            (Reducer::Sum, c) => c.unwrap_or(0.0) + value,
+            (Reducer::Min, Some(c)) => c.min(value),
+            (Reducer::Max, Some(c)) => c.max(value),
+            (Reducer::Min | Reducer::Max, None) => value,
+        }
+    }
+}
+
+/// Bucket the timestamped values in the local day of `zone` they happen on, and reduce the
+/// values of each day.
+pub fn aggregate_days<Tz, Z, I>(events: I, zone: &Z, reducer: Reducer) -> BTreeMap<NaiveDate, f64>
+where
+    Tz: TimeZone,
+    Z: TimeZone,
+    I: IntoIterator<Item = (DateTime<Tz>, f64)>,
+{
+    let mut days = BTreeMap::new();
+    for (time, value) in events {
+        let day = time.with_timezone(zone).date_naive();
+        let current = days.get(&day).cloned();
+        days.insert(day, reducer.reduce(current, value));
+    }
+    days
+}
+
+/// Value for each day, mapped to elements by a scale, the days without a value get the
+/// element of zero.
+pub struct DailyValues<E, S> {
+    values: BTreeMap<NaiveDate, f64>,
+    scale: S,
+    element: PhantomData<E>,
+}
+
+impl<E: Element, S: Scale<E>> DailyValues<E, S> {
+    pub fn new(values: BTreeMap<NaiveDate, f64>, scale: S) -> Self {
+        Self {
+            values,
+            scale,
+            element: PhantomData,
+        }
+    }
+
+    /// Aggregate the values of the events in the local days of `zone`.
+    pub fn aggregate<Tz, Z, I>(events: I, zone: &Z, reducer: Reducer, scale: S) -> Self
+    where
+        Tz: TimeZone,
+        Z: TimeZone,
+        I: IntoIterator<Item = (DateTime<Tz>, f64)>,
+    {
+        Self::new(aggregate_days(events, zone, reducer), scale)
+    }
+
+    pub fn value(&self, date: NaiveDate) -> Option<f64> {
+        self.values.get(&date).cloned()
+    }
+
+    /// Highest value of a single day, None without values.
+    pub fn max_value(&self) -> Option<f64> {
+        self.values.values().cloned().reduce(f64::max)
+    }
+
+    pub fn values(&self) -> &BTreeMap<NaiveDate, f64> {
+        &self.values
+    }
+}
+
+impl<E: Element, S: Scale<E>> DateDataSource<E> for DailyValues<E, S> {
+    fn get_element(&self, data: NaiveDate) -> E {
+        self.scale.element(self.value(data).unwrap_or(0.0))
+    }
+}
@@ -1,3 +1,4 @@
+use crate::Info;
 use builder_pattern::Builder;
 
 #[derive(Default, Clone)]
@@ -32,13 +33,47 @@ pub struct Config {
     #[default(3)]
     #[public]
     rounding: u32,
-    #[default(Mode::RowColumn)]
+    /// Placement of the first and second axis, when not set each renderer uses its own
+    /// default, `Mode::RowColumn` for the generic tiles.
+    #[default(None)]
+    #[into]
     #[public]
-    mode: Mode,
+    mode: Option<Mode>,
+    /// Font size of the glyphs drawn inside the tiles.
+    #[default(10)]
+    #[public]
+    glyph_size: u32,
+    /// Extra space added at each group boundary.
+    #[default(10)]
+    #[public]
+    group_gap: u32,
+    /// Indexes on the first axis where a new group starts.
+    #[default(Vec::new())]
+    #[public]
+    first_groups: Vec<u32>,
+    /// Indexes on the second axis where a new group starts.
+    #[default(Vec::new())]
+    #[public]
+    second_groups: Vec<u32>,
+}
+
+/// Compute the group starts matching the spans of the given infos, to be used as
+/// `first_groups` or `second_groups` so the gaps stay aligned with the labels.
+pub fn group_starts<I: Info>(infos: impl Iterator<Item = I>) -> Vec<u32> {
+    let mut start = 0;
+    let mut groups = Vec::new();
+    for info in infos {
+        if start > 0 {
+            groups.push(start);
+        }
+        start += info.block_count();
+    }
+    groups
 }
 
-fn coordinate(base: u32, size: u32, padding: u32, offset: u32) -> u32 {
-    offset + (base * (size + padding))
+fn coordinate(base: u32, size: u32, padding: u32, offset: u32, groups: &[u32], gap: u32) -> u32 {
+    let gaps = groups.iter().filter(|g| **g > 0 && **g <= base).count() as u32;
+    offset + (base * (size + padding)) + gaps * gap
 }
 
 impl Config {
@@ -46,18 +81,140 @@ impl Config {
         format!("{}", self.size_x)
     }
     pub(crate) fn size_y(&self) -> String {
-        format!("{}", self.size_x)
+        format!("{}", self.size_y)
+    }
+
+    fn groups_x(&self) -> &[u32] {
+        match self.mode() {
+            Mode::RowColumn => &self.second_groups,
+            Mode::ColumnRow => &self.first_groups,
+        }
+    }
+
+    fn groups_y(&self) -> &[u32] {
+        match self.mode() {
+            Mode::RowColumn => &self.first_groups,
+            Mode::ColumnRow => &self.second_groups,
+        }
     }
 
     fn coordinate_x(&self, value: u32) -> u32 {
-        coordinate(value, self.size_x, self.padding_x, self.offset_x)
+        coordinate(
+            value,
+            self.size_x,
+            self.padding_x,
+            self.offset_x,
+            self.groups_x(),
+            self.group_gap,
+        )
     }
 
     fn coordinate_y(&self, value: u32) -> u32 {
-        coordinate(value, self.size_y, self.padding_y, self.offset_y)
+        coordinate(
+            value,
+            self.size_y,
+            self.padding_y,
+            self.offset_y,
+            self.groups_y(),
+            self.group_gap,
+        )
+    }
+    /// Coordinate where the cell at `first` starts along the first axis.
+    pub(crate) fn first_coordinate(&self, first: u32) -> f64 {
+        match self.mode() {
+            Mode::RowColumn => self.coordinate_y(first) as f64,
+            Mode::ColumnRow => self.coordinate_x(first) as f64,
+        }
+    }
+
+    /// Coordinate where the cell at `second` starts along the second axis.
+    pub(crate) fn second_coordinate(&self, second: u32) -> f64 {
+        match self.mode() {
+            Mode::RowColumn => self.coordinate_x(second) as f64,
+            Mode::ColumnRow => self.coordinate_y(second) as f64,
+        }
+    }
+
+    pub(crate) fn first_size(&self) -> f64 {
+        match self.mode() {
+            Mode::RowColumn => self.size_y as f64,
+            Mode::ColumnRow => self.size_x as f64,
+        }
+    }
+
+    pub(crate) fn second_size(&self) -> f64 {
+        match self.mode() {
+            Mode::RowColumn => self.size_x as f64,
+            Mode::ColumnRow => self.size_y as f64,
+        }
+    }
+
+    pub(crate) fn first_padding(&self) -> f64 {
+        match self.mode() {
+            Mode::RowColumn => self.padding_y as f64,
+            Mode::ColumnRow => self.padding_x as f64,
+        }
+    }
+
+    pub(crate) fn second_padding(&self) -> f64 {
+        match self.mode() {
+            Mode::RowColumn => self.padding_x as f64,
+            Mode::ColumnRow => self.padding_y as f64,
+        }
+    }
+
+    /// Center of the cell as `(x, y)`.
+    pub(crate) fn center(&self, first: u32, second: u32) -> (f64, f64) {
+        let (x, y) = self.point(self.first_coordinate(first), self.second_coordinate(second));
+        (x + self.size_x as f64 / 2.0, y + self.size_y as f64 / 2.0)
     }
+
+    /// Width and height of a tile scaled by `scale`.
+    pub(crate) fn scaled_size(&self, scale: f64) -> (f64, f64) {
+        (self.size_x as f64 * scale, self.size_y as f64 * scale)
+    }
+
+    /// Check if the text can be drawn inside a tile, assuming the width of a character being
+    /// 60% of the font size.
+    pub(crate) fn fits_glyph(&self, text: &str) -> bool {
+        let width = text.chars().count() as f64 * self.glyph_size as f64 * 0.6;
+        self.glyph_size <= self.size_y && width <= self.size_x as f64
+    }
+
+    pub(crate) fn glyph_size(&self) -> String {
+        format!("{}", self.glyph_size)
+    }
+
+    /// Map a point expressed along the first and second axis to `(x, y)`.
+    pub(crate) fn point(&self, first: f64, second: f64) -> (f64, f64) {
+        match self.mode() {
+            Mode::RowColumn => (second, first),
+            Mode::ColumnRow => (first, second),
+        }
+    }
+
+    /// Whether the first axis runs horizontally.
+    pub(crate) fn first_horizontal(&self) -> bool {
+        matches!(self.mode(), Mode::ColumnRow)
+    }
+
+    /// Corner of the area reserved to a cell, including half of the padding around it. Along
+    /// each axis the edge is the opening one of the cell, or when `closing` the one ending the
+    /// cell before, leaving out the gap of a group starting at the cell.
+    pub(crate) fn edge(&self, first: u32, second: u32, closing: (bool, bool)) -> (f64, f64) {
+        let first = match first.checked_sub(1).filter(|_| closing.0) {
+            Some(before) => self.first_coordinate(before) + self.first_size() + self.first_padding() / 2.0,
+            None => self.first_coordinate(first) - self.first_padding() / 2.0,
+        };
+        let second = match second.checked_sub(1).filter(|_| closing.1) {
+            Some(before) => self.second_coordinate(before) + self.second_size() + self.second_padding() / 2.0,
+            None => self.second_coordinate(second) - self.second_padding() / 2.0,
+        };
+        self.point(first, second)
+    }
+
     pub(crate) fn positions(&self, first: u32, second: u32) -> (String, String) {
-        let (x, y) = match self.mode {
+        let (x, y) = match self.mode() {
             Mode::RowColumn => (second, first),
             Mode::ColumnRow => (first, second),
         };
@@ -69,26 +226,26 @@ impl Config {
     }
 
     pub(crate) fn set_metadata_first_offset(&mut self, item: u32) {
-        match self.mode {
+        match self.mode() {
             Mode::RowColumn => self.offset_y += item,
             Mode::ColumnRow => self.offset_x += item,
         };
     }
 
     pub(crate) fn set_metadata_after_first_offset(&mut self, max_first: u32) {
-        match self.mode {
+        match self.mode() {
             Mode::RowColumn => self.offset_y = self.coordinate_y(max_first),
             Mode::ColumnRow => self.offset_x = self.coordinate_x(max_first),
         };
     }
     pub(crate) fn set_metadata_second_offset(&mut self, item: u32) {
-        match self.mode {
+        match self.mode() {
             Mode::RowColumn => self.offset_x += item,
             Mode::ColumnRow => self.offset_y += item,
         };
     }
     pub(crate) fn set_metadata_after_second_offset(&mut self, max_second: u32) {
-        match self.mode {
+        match self.mode() {
             Mode::RowColumn => self.offset_x = self.coordinate_x(max_second),
             Mode::ColumnRow => self.offset_y = self.coordinate_y(max_second),
         };
@@ -99,7 +256,39 @@ impl Config {
     pub(crate) fn rounding(&self) -> String {
         format!("{}", self.rounding)
     }
+    #[cfg(feature = "year_line")]
+    /// Distance between the start of two consecutive cells on the x and y axis.
+    pub(crate) fn step(&self) -> (u32, u32) {
+        (self.size_x + self.padding_x, self.size_y + self.padding_y)
+    }
+    #[cfg(feature = "year_line")]
+    pub(crate) fn offset(&self) -> (u32, u32) {
+        (self.offset_x, self.offset_y)
+    }
+    #[cfg(feature = "year_line")]
+    pub(crate) fn second_groups(&self) -> &[u32] {
+        &self.second_groups
+    }
+    #[cfg(feature = "year_line")]
+    pub(crate) fn translate(&mut self, x: u32, y: u32) {
+        self.offset_x += x;
+        self.offset_y += y;
+    }
+    fn mode(&self) -> Mode {
+        self.mode.clone().unwrap_or_default()
+    }
     pub fn set_mode(&mut self, mode: Mode) {
-        self.mode = mode;
+        self.mode = Some(mode);
+    }
+    /// Use the mode only if the caller did not configure one.
+    #[cfg(feature = "year_line")]
+    pub(crate) fn default_mode(&mut self, mode: Mode) {
+        self.mode.get_or_insert(mode);
+    }
+    pub fn set_first_groups(&mut self, groups: Vec<u32>) {
+        self.first_groups = groups;
+    }
+    pub fn set_second_groups(&mut self, groups: Vec<u32>) {
+        self.second_groups = groups;
     }
 }
@@ -4,6 +4,23 @@ pub trait Element {
     fn get_color(&self) -> Rgb;
     fn get_border_color(&self) -> Rgb;
     fn get_link(&self) -> Option<Box<dyn ElementLink>>;
+    /// Optional short text drawn centered inside the tile, dropped if the tile is too small.
+    fn glyph(&self) -> Option<String> {
+        None
+    }
+    /// When false the tile is not drawn, leaving its place empty.
+    fn is_visible(&self) -> bool {
+        true
+    }
+    /// Size of the tile relative to the full cell, between 0 and 1, the tile stays centered in
+    /// the cell.
+    fn scale(&self) -> f64 {
+        1.0
+    }
+    /// Optional value represented by the tile, used by the statistics.
+    fn value(&self) -> Option<f64> {
+        None
+    }
 }
 
 pub trait ElementLink {
@@ -13,7 +30,16 @@ pub trait ElementLink {
 
 pub trait Info {
     fn block_count(&self) -> u32;
+    /// Text of the label, each `\n` separated line is rendered on its own line.
     fn label(&self) -> &str;
+    /// Optional link target of the label.
+    fn link(&self) -> Option<String> {
+        None
+    }
+    /// Optional tooltip shown when hovering the label.
+    fn tooltip(&self) -> Option<String> {
+        None
+    }
 }
 
 pub trait Metadata<IT, IF>
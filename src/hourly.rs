@@ -0,0 +1,122 @@
+use crate::{
+    metadata_tile,
+    month::month_range,
+    year::{month_labels_size, CalendarNames, YearInfo},
+    Config, Element, Metadata, Mode,
+};
+use builder_pattern::Builder;
+use chrono::{Datelike, Days, Locale, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use std::io::Write;
+use std::rc::Rc;
+
+/// Hours grouped under each hour label.
+const HOURS_LABEL_SPAN: u32 = 6;
+
+#[derive(Builder)]
+pub struct HourlyOptions {
+    /// Locale used for the month names.
+    #[default(Locale::en_US)]
+    #[public]
+    locale: Locale,
+}
+
+pub trait DateTimeDataSource<E: Element> {
+    /// Element of the hour starting at `data`.
+    fn get_element(&self, data: NaiveDateTime) -> E;
+}
+
+impl<E: Element, F: Fn(NaiveDateTime) -> E> DateTimeDataSource<E> for F {
+    fn get_element(&self, data: NaiveDateTime) -> E {
+        self(data)
+    }
+}
+
+struct HourlyMetadata {
+    year: i32,
+    names: CalendarNames,
+    /// Whether the days are columns, with the month labels on top.
+    days_columns: bool,
+}
+
+impl Metadata<std::vec::IntoIter<YearInfo>, YearInfo> for HourlyMetadata {
+    fn left_size(&self) -> u32 {
+        month_labels_size(self.days_columns)
+    }
+
+    fn top_size(&self) -> u32 {
+        20
+    }
+
+    fn left(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        Some(
+            (1..=12)
+                .filter_map(|month| {
+                    let (start, end) = month_range(self.year, month)?;
+                    Some(YearInfo::new(
+                        &self.names.month_name(month),
+                        end.day() - start.day() + 1,
+                    ))
+                })
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    fn top(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        Some(YearInfo::hours(HOURS_LABEL_SPAN, |hour| format!("{:02}", hour)))
+    }
+
+    fn right(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+
+    fn bottom(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+}
+
+/// Render every hour of the year, with a column for each day and a row for each hour.
+pub fn hourly_year_line<W, D, E>(
+    year: i32,
+    data_source: D,
+    output: W,
+    config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateTimeDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    hourly_year_line_with_options(year, data_source, output, config, HourlyOptions::new().build())
+}
+
+pub fn hourly_year_line_with_options<W, D, E>(
+    year: i32,
+    data_source: D,
+    output: W,
+    mut config: Config,
+    options: HourlyOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateTimeDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    let start = NaiveDate::from_ymd_opt(year, 1, 1).ok_or_else(|| format!("invalid year {}", year))?;
+    let end = NaiveDate::from_ymd_opt(year, 12, 31).ok_or_else(|| format!("invalid year {}", year))?;
+    config.default_mode(Mode::ColumnRow);
+    let metadata = HourlyMetadata {
+        year,
+        names: CalendarNames::new(Weekday::Sun, options.locale),
+        days_columns: config.first_horizontal(),
+    };
+    let data_source = Rc::new(data_source);
+    let days = end.signed_duration_since(start).num_days() as u64 + 1;
+    let data = (0..days).map(move |day| {
+        let date = start + Days::new(day);
+        let data_source = data_source.clone();
+        (0..24).map(move |hour| data_source.get_element(date.and_time(NaiveTime::from_hms_opt(hour, 0, 0).unwrap())))
+    });
+    metadata_tile(config, metadata, data, output)?;
+    Ok(())
+}
@@ -1,18 +1,60 @@
-use crate::pieces::{base_doc, write_after_metadata, write_metadata, write_tile};
+use crate::pieces::{base_doc, write_metadata_tile, write_overlays, write_tile};
 pub use colorsys::Rgb;
 use std::{io::Write, sync::Mutex};
 
+#[cfg(feature = "year_line")]
+mod aggregate;
+#[cfg(feature = "year_line")]
+pub use aggregate::{aggregate_days, DailyValues, Reducer};
 mod config;
-pub use config::{Config, Mode};
+pub use config::{group_starts, Config, Mode};
 mod data_traits;
 pub use data_traits::{Element, ElementLink, Info, Metadata};
+#[cfg(feature = "year_line")]
+mod hourly;
+#[cfg(feature = "year_line")]
+pub use hourly::{hourly_year_line, hourly_year_line_with_options, DateTimeDataSource, HourlyOptions};
+#[cfg(feature = "year_line")]
+mod life;
+#[cfg(feature = "year_line")]
+pub use life::{life_weeks, LifeColors, LifeDataSource, LifeWeek, WeekState};
+#[cfg(feature = "year_line")]
+mod month;
+#[cfg(feature = "year_line")]
+pub use month::{month_grid, month_grid_with_options, wall_calendar, wall_calendar_with_options, WallCalendarOptions};
+mod overlay;
+pub use overlay::{Annotation, AnnotationStyle, Bar, Outline, Overlay};
 mod pieces;
 #[cfg(feature = "year_line")]
+mod punch_card;
+#[cfg(feature = "year_line")]
+pub use punch_card::{punch_card, punch_card_with_options, PunchCard, PunchCardOptions};
+mod scale;
+pub use scale::{ColorScale, Scale, ScaledElement, SizeScale};
+#[cfg(feature = "year_line")]
+mod sources;
+#[cfg(feature = "year_line")]
+pub use sources::{ical_dates, EventCounts, EventDate, EventTime};
+#[cfg(feature = "year_line")]
+mod stats;
+#[cfg(feature = "year_line")]
+pub use stats::DateStats;
+#[cfg(feature = "year_line")]
+mod weeks;
+#[cfg(feature = "year_line")]
+pub use weeks::{weeks_line, WeekDataSource, WeeklyAggregate};
+#[cfg(feature = "year_line")]
 mod year;
 #[cfg(feature = "year_line")]
-pub use chrono::{Datelike, NaiveDate};
+pub use chrono::{DateTime, Datelike, FixedOffset, IsoWeek, Locale, NaiveDate, NaiveDateTime, Utc, Weekday};
+#[cfg(feature = "chrono-tz")]
+pub use chrono_tz::Tz;
 #[cfg(feature = "year_line")]
-pub use year::{year_line, DateDataSource};
+pub use year::{
+    date_range_line, date_range_line_with_options, multi_year_line, multi_year_line_with_options, year_line,
+    year_line_with_options, years_line, years_line_with_options, DateDataSource, DayOptions, MultiDateDataSource,
+    Padding, WeekLabels, YearOptions,
+};
 
 pub fn metadata_tile<D, B, E, W, M, MIT, MIN>(
     config: Config,
@@ -20,6 +62,25 @@ pub fn metadata_tile<D, B, E, W, M, MIT, MIN>(
     data_source: D,
     output: W,
 ) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: Iterator<Item = B>,
+    B: Iterator<Item = E>,
+    E: Element,
+    W: Write,
+    M: Metadata<MIT, MIN>,
+    MIT: Iterator<Item = MIN>,
+    MIN: Info,
+{
+    metadata_tile_with_overlays(config, metadata, data_source, Vec::new(), output)
+}
+
+pub fn metadata_tile_with_overlays<D, B, E, W, M, MIT, MIN>(
+    config: Config,
+    metadata: M,
+    data_source: D,
+    overlays: Vec<Overlay>,
+    output: W,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
 where
     D: Iterator<Item = B>,
     B: Iterator<Item = E>,
@@ -31,14 +92,8 @@ where
 {
     let ds = Mutex::new(Some((config, data_source, metadata)));
     base_doc(output, move |svg| {
-        let (mut config, data_source, metadata) = ds.lock().unwrap().take().unwrap();
-        let top_size = metadata.top_size();
-        let left_size = metadata.left_size();
-        write_metadata(svg, &config, &metadata)?;
-        config.set_metadata_first_offset(top_size);
-        config.set_metadata_second_offset(left_size);
-        let (max_first, max_second) = write_tile(svg, data_source, &config)?;
-        write_after_metadata(svg, &config, metadata, max_first, max_second)?;
+        let (config, data_source, metadata) = ds.lock().unwrap().take().unwrap();
+        write_metadata_tile(svg, config, metadata, data_source, &overlays)?;
         Ok(())
     })?;
     Ok(())
@@ -49,6 +104,21 @@ pub fn tile<D, B, E, W>(
     data_source: D,
     output: W,
 ) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: Iterator<Item = B>,
+    B: Iterator<Item = E>,
+    E: Element,
+    W: Write,
+{
+    tile_with_overlays(config, data_source, Vec::new(), output)
+}
+
+pub fn tile_with_overlays<D, B, E, W>(
+    config: Config,
+    data_source: D,
+    overlays: Vec<Overlay>,
+    output: W,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
 where
     D: Iterator<Item = B>,
     B: Iterator<Item = E>,
@@ -58,7 +128,8 @@ where
     let ds = Mutex::new(Some(data_source));
     base_doc(output, move |svg| {
         let data_source = ds.lock().unwrap().take().unwrap();
-        write_tile(svg, data_source, &config)?;
+        let (_, max_second) = write_tile(svg, data_source, &config)?;
+        write_overlays(svg, &config, &overlays, max_second, 0)?;
         Ok(())
     })?;
     Ok(())
@@ -0,0 +1,193 @@
+use crate::{metadata_tile, year::YearInfo, Config, Element, Metadata, Mode, Rgb};
+use chrono::{Days, Months, NaiveDate};
+use std::io::Write;
+
+/// Weeks drawn for each year of age, the last week of the year takes the remaining days.
+const WEEKS_PER_YEAR: u32 = 52;
+/// Years grouped under each age label.
+const DECADE: u32 = 10;
+
+/// Position of a week relative to today.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WeekState {
+    Past,
+    Current,
+    Future,
+}
+
+/// A week of the life grid, with the year of age and the week in that year counted from zero.
+#[derive(Clone, Debug)]
+pub struct LifeWeek {
+    start: NaiveDate,
+    end: NaiveDate,
+    age: u32,
+    week: u32,
+    state: WeekState,
+}
+
+impl LifeWeek {
+    /// First day of the week.
+    pub fn start(&self) -> NaiveDate {
+        self.start
+    }
+
+    /// Last day of the week.
+    pub fn end(&self) -> NaiveDate {
+        self.end
+    }
+
+    pub fn age(&self) -> u32 {
+        self.age
+    }
+
+    pub fn week(&self) -> u32 {
+        self.week
+    }
+
+    pub fn state(&self) -> WeekState {
+        self.state
+    }
+}
+
+pub trait LifeDataSource<E: Element> {
+    fn get_element(&self, week: &LifeWeek) -> E;
+}
+
+impl<E: Element, F: Fn(&LifeWeek) -> E> LifeDataSource<E> for F {
+    fn get_element(&self, week: &LifeWeek) -> E {
+        self(week)
+    }
+}
+
+/// Color for each state of the weeks.
+#[derive(Clone)]
+pub struct LifeColors {
+    past: Rgb,
+    current: Rgb,
+    future: Rgb,
+}
+
+impl LifeColors {
+    pub fn new(past: Rgb, current: Rgb, future: Rgb) -> Self {
+        Self { past, current, future }
+    }
+}
+
+impl Default for LifeColors {
+    /// Dark grey for the past, red for the current week and light grey for the future.
+    fn default() -> Self {
+        Self::new(
+            Rgb::new(80.0, 80.0, 80.0, None),
+            Rgb::new(220.0, 30.0, 30.0, None),
+            Rgb::new(235.0, 237.0, 240.0, None),
+        )
+    }
+}
+
+impl LifeDataSource<(Rgb, Rgb, Option<(String, String)>)> for LifeColors {
+    fn get_element(&self, week: &LifeWeek) -> (Rgb, Rgb, Option<(String, String)>) {
+        let color = match week.state {
+            WeekState::Past => self.past.clone(),
+            WeekState::Current => self.current.clone(),
+            WeekState::Future => self.future.clone(),
+        };
+        (color.clone(), color, None)
+    }
+}
+
+/// Anniversary of the start date at the given age, the 29th of February falls back to the
+/// 28th in the common years.
+fn anniversary(start: NaiveDate, age: u32) -> Option<NaiveDate> {
+    start.checked_add_months(Months::new(age.checked_mul(12)?))
+}
+
+fn life_week(start: NaiveDate, age: u32, week: u32, today: NaiveDate) -> Option<LifeWeek> {
+    let week_start = anniversary(start, age)?.checked_add_days(Days::new(week as u64 * 7))?;
+    let next = if week + 1 == WEEKS_PER_YEAR {
+        anniversary(start, age.checked_add(1)?)?
+    } else {
+        week_start.checked_add_days(Days::new(7))?
+    };
+    let state = if next <= today {
+        WeekState::Past
+    } else if week_start <= today {
+        WeekState::Current
+    } else {
+        WeekState::Future
+    };
+    Some(LifeWeek {
+        start: week_start,
+        end: next.pred_opt()?,
+        age,
+        week,
+        state,
+    })
+}
+
+struct LifeMetadata {
+    years: u32,
+}
+
+impl Metadata<std::vec::IntoIter<YearInfo>, YearInfo> for LifeMetadata {
+    fn left_size(&self) -> u32 {
+        30
+    }
+
+    fn top_size(&self) -> u32 {
+        0
+    }
+
+    fn left(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        Some(
+            (0..self.years)
+                .step_by(DECADE as usize)
+                .map(|age| YearInfo::new(&format!("{}", age), DECADE.min(self.years - age)))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    fn top(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+
+    fn right(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+
+    fn bottom(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+}
+
+/// Render the "life in weeks" grid, a row of 52 weeks for each of the `years` from `start`.
+pub fn life_weeks<W, D, E>(
+    start: NaiveDate,
+    years: u32,
+    today: NaiveDate,
+    data_source: D,
+    output: W,
+    mut config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: LifeDataSource<E>,
+    E: Element,
+    W: Write,
+{
+    if years == 0 {
+        return Err("life weeks need at least one year".into());
+    }
+    anniversary(start, years).ok_or_else(|| format!("{} years from {} are out of range", years, start))?;
+    config.default_mode(Mode::RowColumn);
+    let rows = (0..years)
+        .map(|age| {
+            (0..WEEKS_PER_YEAR)
+                .filter_map(|week| life_week(start, age, week, today))
+                .map(|week| data_source.get_element(&week))
+                .collect::<Vec<_>>()
+                .into_iter()
+        })
+        .collect::<Vec<_>>();
+    metadata_tile(config, LifeMetadata { years }, rows.into_iter(), output)?;
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use crate::{
+    metadata_tile_with_overlays,
+    pieces::{base_doc, write_free_text, write_metadata_tile},
+    year::{CalendarNames, DateLayout, DayStyle, Weeks, YearInfo, BLOCK_SPACING, TITLE_SIZE},
+    Config, DateDataSource, DayOptions, Element, Metadata, Mode,
+};
+use builder_pattern::Builder;
+use chrono::{NaiveDate, Weekday};
+use std::io::Write;
+use std::rc::Rc;
+
+#[derive(Builder)]
+pub struct WallCalendarOptions {
+    #[default(DayOptions::new().build())]
+    #[public]
+    days: DayOptions,
+    /// Count of months on each row.
+    #[default(3)]
+    #[public]
+    columns: u32,
+}
+
+#[derive(Clone)]
+struct MonthMetadata {
+    names: CalendarNames,
+}
+
+impl Metadata<std::vec::IntoIter<YearInfo>, YearInfo> for MonthMetadata {
+    fn left_size(&self) -> u32 {
+        0
+    }
+
+    fn top_size(&self) -> u32 {
+        20
+    }
+
+    fn left(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+
+    fn top(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        Some(
+            (0..7)
+                .map(|day| YearInfo::new(&self.names.weekday_initial(day), 1))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    fn right(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+
+    fn bottom(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+}
+
+/// First and last day of the month.
+pub(crate) fn month_range(year: i32, month: u32) -> Option<(NaiveDate, NaiveDate)> {
+    let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)?
+    };
+    Some((start, next.pred_opt()?))
+}
+
+/// Layout of the days of the month.
+fn month_layout(year: i32, month: u32, week_start: Weekday) -> Option<DateLayout> {
+    let (start, end) = month_range(year, month)?;
+    DateLayout::new(start, end, week_start)
+}
+
+/// Render a month calendar with a column for each day of the week and a row for each week,
+/// every day shows its day of the month.
+pub fn month_grid<W, D, E>(
+    year: i32,
+    month: u32,
+    data_source: D,
+    output: W,
+    config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    month_grid_with_options(year, month, data_source, output, config, DayOptions::new().build())
+}
+
+pub fn month_grid_with_options<W, D, E>(
+    year: i32,
+    month: u32,
+    data_source: D,
+    output: W,
+    mut config: Config,
+    options: DayOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    let layout =
+        month_layout(year, month, options.week_start()).ok_or_else(|| format!("invalid month {}-{}", year, month))?;
+    config.set_mode(Mode::RowColumn);
+    let metadata = MonthMetadata {
+        names: CalendarNames::new(options.week_start(), options.locale()),
+    };
+    let style = Rc::new(DayStyle::new(&options, true));
+    let overlays = options.day_overlays(&layout);
+    metadata_tile_with_overlays(
+        config,
+        metadata,
+        Weeks::new(layout, Rc::new(data_source), style),
+        overlays,
+        output,
+    )?;
+    Ok(())
+}
+
+/// Render the twelve months of the year as month grids, arranged in rows of
+/// `columns` months each with its title.
+pub fn wall_calendar<W, D, E>(
+    year: i32,
+    data_source: D,
+    output: W,
+    config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    wall_calendar_with_options(year, data_source, output, config, WallCalendarOptions::new().build())
+}
+
+pub fn wall_calendar_with_options<W, D, E>(
+    year: i32,
+    data_source: D,
+    output: W,
+    mut config: Config,
+    options: WallCalendarOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    let layouts = (1..=12)
+        .map(|month| {
+            month_layout(year, month, options.days.week_start()).ok_or_else(|| format!("invalid year {}", year))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    config.set_mode(Mode::RowColumn);
+    let metadata = MonthMetadata {
+        names: CalendarNames::new(options.days.week_start(), options.days.locale()),
+    };
+    let columns = options.columns.max(1);
+    let (step_x, step_y) = config.step();
+    let width = 7 * step_x + BLOCK_SPACING;
+    let height = TITLE_SIZE + metadata.top_size() + 6 * step_y + BLOCK_SPACING;
+    let data_source: Rc<dyn DateDataSource<E>> = Rc::new(data_source);
+    let style = Rc::new(DayStyle::new(&options.days, true));
+    base_doc(output, move |svg| {
+        for (index, layout) in layouts.iter().enumerate() {
+            let index = index as u32;
+            let mut c = config.clone();
+            c.translate((index % columns) * width, (index / columns) * height);
+            let (x, y) = c.offset();
+            write_free_text(
+                svg,
+                x as f64,
+                y as f64,
+                "hanging",
+                &metadata.names.month_full_name(index + 1),
+            )?;
+            c.translate(0, TITLE_SIZE);
+            let overlays = options.days.day_overlays(layout);
+            let weeks = Weeks::new(layout.clone(), data_source.clone(), style.clone());
+            write_metadata_tile(svg, c, metadata.clone(), weeks, &overlays)?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
@@ -0,0 +1,323 @@
+use crate::{Element, Rgb};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+/// Shape drawn on top of the tiles once all of them are written.
+pub enum Overlay {
+    Outline(Outline),
+    Annotation(Annotation),
+    Bar(Bar),
+}
+
+impl Overlay {
+    /// Move the overlay by `offset` cells along the second axis, like into a stacked series.
+    #[cfg(feature = "year_line")]
+    pub(crate) fn shift_second(self, offset: u32) -> Self {
+        let shift = |(first, second): &(u32, u32)| (*first, second + offset);
+        match self {
+            Overlay::Outline(outline) => Overlay::Outline(Outline {
+                cells: outline.cells.iter().map(shift).collect(),
+                ..outline
+            }),
+            Overlay::Annotation(annotation) => Overlay::Annotation(annotation.map(shift)),
+            Overlay::Bar(bar) => Overlay::Bar(bar.map(shift)),
+        }
+    }
+}
+
+/// Rounded bar over the consecutive cells between `from` and `to` inclusive, like a date interval.
+pub struct Bar<R = (u32, u32)> {
+    from: R,
+    to: R,
+    element: Arc<dyn Element + Send + Sync>,
+}
+
+impl<R> Bar<R> {
+    /// The bar takes the colors and the link of the element.
+    pub fn new(from: R, to: R, element: impl Element + Send + Sync + 'static) -> Self {
+        Self {
+            from,
+            to,
+            element: Arc::new(element),
+        }
+    }
+
+    #[cfg(feature = "year_line")]
+    pub(crate) fn bounds(&self) -> (&R, &R) {
+        (&self.from, &self.to)
+    }
+
+    #[cfg(feature = "year_line")]
+    pub(crate) fn map<T>(&self, f: impl Fn(&R) -> T) -> Bar<T> {
+        Bar {
+            from: f(&self.from),
+            to: f(&self.to),
+            element: self.element.clone(),
+        }
+    }
+}
+
+impl<R, E: Element + Send + Sync + 'static> From<(R, R, E)> for Bar<R> {
+    fn from((from, to, element): (R, R, E)) -> Self {
+        Self::new(from, to, element)
+    }
+}
+
+impl Bar {
+    /// Runs of cells covered by the bar as `(first, second_start, second_end)`.
+    pub(crate) fn segments(&self, seconds: u32) -> Vec<(u32, u32, u32)> {
+        let (from, to) = if self.from <= self.to {
+            (self.from, self.to)
+        } else {
+            (self.to, self.from)
+        };
+        let last = seconds.saturating_sub(1);
+        (from.0..=to.0)
+            .map(|first| {
+                let start = if first == from.0 { from.1 } else { 0 };
+                let end = if first == to.0 { to.1 } else { last };
+                (first, start, end)
+            })
+            .filter(|(_, start, end)| start <= end)
+            .collect()
+    }
+
+    pub(crate) fn element(&self) -> &dyn Element {
+        self.element.as_ref()
+    }
+}
+
+#[derive(Default, Clone)]
+pub enum AnnotationStyle {
+    /// Bracket spanning the whole range.
+    #[default]
+    Bracket,
+    /// Marker pointing to the start of the range.
+    Marker,
+}
+
+/// Callout text attached to a range of cells, drawn in the band after the tiles.
+pub struct Annotation<R = (u32, u32)> {
+    from: R,
+    to: R,
+    text: String,
+    style: AnnotationStyle,
+    color: Rgb,
+}
+
+impl<R> Annotation<R> {
+    pub fn new(from: R, to: R, text: &str) -> Self {
+        Self {
+            from,
+            to,
+            text: text.to_owned(),
+            style: AnnotationStyle::default(),
+            color: Rgb::new(0.0, 0.0, 0.0, None),
+        }
+    }
+
+    pub fn style(mut self, style: AnnotationStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn color(mut self, color: Rgb) -> Self {
+        self.color = color;
+        self
+    }
+
+    #[cfg(feature = "year_line")]
+    pub(crate) fn bounds(&self) -> (&R, &R) {
+        (&self.from, &self.to)
+    }
+
+    #[cfg(feature = "year_line")]
+    pub(crate) fn map<T>(&self, f: impl Fn(&R) -> T) -> Annotation<T> {
+        Annotation {
+            from: f(&self.from),
+            to: f(&self.to),
+            text: self.text.clone(),
+            style: self.style.clone(),
+            color: self.color.clone(),
+        }
+    }
+}
+
+impl Annotation {
+    /// First and last index on the first axis covered by the annotation.
+    pub(crate) fn first_range(&self) -> (u32, u32) {
+        (self.from.0.min(self.to.0), self.from.0.max(self.to.0))
+    }
+
+    pub(crate) fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub(crate) fn get_style(&self) -> &AnnotationStyle {
+        &self.style
+    }
+
+    pub(crate) fn get_color(&self) -> &Rgb {
+        &self.color
+    }
+}
+
+/// Distance of each annotation from the tiles, overlapping annotations get separate lanes.
+pub(crate) fn annotation_lanes(annotations: &[&Annotation], text_size: impl Fn(&str) -> f64) -> Vec<f64> {
+    let mut order = (0..annotations.len()).collect::<Vec<_>>();
+    order.sort_by_key(|i| annotations[*i].first_range());
+    let mut lane_ends: Vec<u32> = Vec::new();
+    let mut lane_sizes: Vec<f64> = Vec::new();
+    let mut lanes = vec![0; annotations.len()];
+    for i in order {
+        let (start, end) = annotations[i].first_range();
+        let size = ANNOTATION_MARK + text_size(annotations[i].get_text());
+        match lane_ends.iter().position(|e| *e < start) {
+            Some(lane) => {
+                lane_ends[lane] = end;
+                lane_sizes[lane] = lane_sizes[lane].max(size);
+                lanes[i] = lane;
+            }
+            None => {
+                lanes[i] = lane_ends.len();
+                lane_ends.push(end);
+                lane_sizes.push(size);
+            }
+        }
+    }
+    let mut offsets = Vec::new();
+    let mut offset = 0.0;
+    for size in lane_sizes {
+        offsets.push(offset);
+        offset += size;
+    }
+    lanes.into_iter().map(|l| offsets[l]).collect()
+}
+
+/// Space taken by the bracket or marker before the text.
+pub(crate) const ANNOTATION_MARK: f64 = 10.0;
+
+/// Border traced along the outer edges of a set of cells.
+pub struct Outline {
+    cells: BTreeSet<(u32, u32)>,
+    color: Rgb,
+    width: u32,
+    label: Option<String>,
+}
+
+impl Outline {
+    /// Outline the given `(first, second)` cells, not necessarily contiguous.
+    pub fn new(cells: impl IntoIterator<Item = (u32, u32)>, color: Rgb) -> Self {
+        Self {
+            cells: cells.into_iter().collect(),
+            color,
+            width: 2,
+            label: None,
+        }
+    }
+
+    /// Outline the rectangular range of cells between `from` and `to` inclusive.
+    pub fn range(from: (u32, u32), to: (u32, u32), color: Rgb) -> Self {
+        let (first_min, first_max) = (from.0.min(to.0), from.0.max(to.0));
+        let (second_min, second_max) = (from.1.min(to.1), from.1.max(to.1));
+        let cells = (first_min..=first_max).flat_map(|f| (second_min..=second_max).map(move |s| (f, s)));
+        Self::new(cells, color)
+    }
+
+    pub fn width(mut self, width: u32) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_owned());
+        self
+    }
+
+    pub(crate) fn color(&self) -> &Rgb {
+        &self.color
+    }
+
+    pub(crate) fn stroke_width(&self) -> u32 {
+        self.width
+    }
+
+    pub(crate) fn get_label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub(crate) fn contains(&self, cell: (u32, u32)) -> bool {
+        self.cells.contains(&cell)
+    }
+
+    /// First cell of the outline, used to place the label.
+    pub(crate) fn first_cell(&self) -> Option<(u32, u32)> {
+        self.cells.iter().next().cloned()
+    }
+
+    /// Closed loops of grid vertexes `(first, second)` walking the outer edges of the cells.
+    pub(crate) fn loops(&self) -> Vec<Vec<(u32, u32)>> {
+        let mut edges: BTreeMap<(u32, u32), Vec<(u32, u32)>> = BTreeMap::new();
+        let mut add = |from: (u32, u32), to: (u32, u32)| edges.entry(from).or_default().push(to);
+        let has = |f: Option<u32>, s: Option<u32>| match (f, s) {
+            (Some(f), Some(s)) => self.cells.contains(&(f, s)),
+            _ => false,
+        };
+        for &(f, s) in &self.cells {
+            if !has(f.checked_sub(1), Some(s)) {
+                add((f, s + 1), (f, s));
+            }
+            if !has(Some(f), s.checked_sub(1)) {
+                add((f, s), (f + 1, s));
+            }
+            if !has(Some(f + 1), Some(s)) {
+                add((f + 1, s), (f + 1, s + 1));
+            }
+            if !has(Some(f), Some(s + 1)) {
+                add((f + 1, s + 1), (f, s + 1));
+            }
+        }
+
+        let mut loops = Vec::new();
+        while let Some(start) = edges.keys().next().cloned() {
+            let mut points = vec![start];
+            let mut current = start;
+            loop {
+                let next = match edges.get_mut(&current) {
+                    Some(targets) => {
+                        let next = targets.pop();
+                        if targets.is_empty() {
+                            edges.remove(&current);
+                        }
+                        next
+                    }
+                    None => None,
+                };
+                match next {
+                    Some(n) if n == start => break,
+                    Some(n) => {
+                        points.push(n);
+                        current = n;
+                    }
+                    None => break,
+                }
+            }
+            loops.push(simplify(points));
+        }
+        loops
+    }
+}
+
+/// Drop the points in the middle of a straight segment.
+fn simplify(points: Vec<(u32, u32)>) -> Vec<(u32, u32)> {
+    let len = points.len();
+    (0..len)
+        .filter(|i| {
+            let prev = points[(i + len - 1) % len];
+            let next = points[(i + 1) % len];
+            let cur = points[*i];
+            !((prev.0 == cur.0 && cur.0 == next.0) || (prev.1 == cur.1 && cur.1 == next.1))
+        })
+        .map(|i| points[i])
+        .collect()
+}
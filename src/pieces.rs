@@ -1,10 +1,18 @@
-use crate::{Config, Element, Info, Metadata};
+use crate::{
+    overlay::{annotation_lanes, ANNOTATION_MARK},
+    Annotation, AnnotationStyle, Bar, Config, Element, Info, Metadata, Outline, Overlay, Rgb,
+};
+use colorsys::ColorAlpha;
 use quick_xml::{
     events::{BytesDecl, BytesText, Event},
     Error, Writer,
 };
 use std::io::Write;
 
+/// Approximate size of a character of the default font, used to lay out free text.
+const TEXT_HEIGHT: f64 = 16.0;
+const TEXT_WIDTH: f64 = 8.0;
+
 pub(crate) fn base_doc<F, W>(output: W, f: F) -> std::result::Result<(), Box<dyn std::error::Error>>
 where
     F: Fn(&mut Writer<W>) -> std::result::Result<(), Error>,
@@ -34,13 +42,21 @@ pub(crate) fn write_rect<W: std::io::Write>(
     second: u32,
     ele: &impl Element,
 ) -> std::result::Result<(), Error> {
-    let style = format!(
-        "fill:{};stroke-width:{};stroke:{}",
-        ele.get_color().to_hex_string(),
-        config.border(),
-        ele.get_border_color().to_hex_string()
-    );
-    let (x, y) = config.positions(first, second);
+    let style = element_style(config, ele);
+    let scale = ele.scale().clamp(0.0, 1.0);
+    let (x, y, width, height) = if scale < 1.0 {
+        let (cx, cy) = config.center(first, second);
+        let (width, height) = config.scaled_size(scale);
+        (
+            format!("{}", cx - width / 2.0),
+            format!("{}", cy - height / 2.0),
+            format!("{}", width),
+            format!("{}", height),
+        )
+    } else {
+        let (x, y) = config.positions(first, second);
+        (x, y, config.size_x(), config.size_y())
+    };
 
     svg.create_element("rect")
         .with_attributes(vec![
@@ -48,29 +64,123 @@ pub(crate) fn write_rect<W: std::io::Write>(
             ("y", y.as_str()),
             ("rx", config.rounding().as_str()),
             ("ry", config.rounding().as_str()),
-            ("width", config.size_x().as_str()),
-            ("height", config.size_y().as_str()),
+            ("width", width.as_str()),
+            ("height", height.as_str()),
             ("style", style.as_str()),
         ])
         .write_empty()?;
+    if let Some(glyph) = ele.glyph().filter(|g| config.fits_glyph(g)) {
+        let (x, y) = config.center(first, second);
+        let (x, y) = (format!("{}", x), format!("{}", y));
+        let fill = contrast_color(&ele.get_color()).to_hex_string();
+        svg.create_element("text")
+            .with_attributes(vec![
+                ("x", x.as_str()),
+                ("y", y.as_str()),
+                ("text-anchor", "middle"),
+                ("dominant-baseline", "central"),
+                ("font-size", config.glyph_size().as_str()),
+                ("fill", fill.as_str()),
+            ])
+            .write_text_content(BytesText::new(&glyph))?;
+    }
     Ok(())
 }
 
+/// Fill and stroke of the element.
+fn element_style(config: &Config, ele: &(impl Element + ?Sized)) -> String {
+    let color = ele.get_color();
+    let border_color = ele.get_border_color();
+    let mut style = format!(
+        "fill:{};stroke-width:{};stroke:{}",
+        color.to_hex_string(),
+        config.border(),
+        border_color.to_hex_string()
+    );
+    if color.alpha() < 1.0 {
+        style.push_str(&format!(";fill-opacity:{}", color.alpha()));
+    }
+    if border_color.alpha() < 1.0 {
+        style.push_str(&format!(";stroke-opacity:{}", border_color.alpha()));
+    }
+    style
+}
+
+/// Black or white, whichever is more readable over the background, following the WCAG
+/// relative luminance.
+pub(crate) fn contrast_color(background: &Rgb) -> Rgb {
+    let linear = |c: f64| {
+        let c = c / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    let luminance =
+        0.2126 * linear(background.red()) + 0.7152 * linear(background.green()) + 0.0722 * linear(background.blue());
+    if luminance > 0.179 {
+        Rgb::new(0.0, 0.0, 0.0, None)
+    } else {
+        Rgb::new(255.0, 255.0, 255.0, None)
+    }
+}
+
 pub(crate) fn write_text<W: std::io::Write>(
     svg: &mut Writer<W>,
     config: &Config,
     x: u32,
     y: u32,
     val: &str,
+    tooltip: Option<&str>,
 ) -> std::result::Result<(), Error> {
     let (x, y) = config.positions(y, x);
-    svg.create_element("text")
-        .with_attributes(vec![
-            ("x", x.as_str()),
-            ("y", y.as_str()),
-            ("dominant-baseline", "hanging"),
-        ])
-        .write_text_content(BytesText::new(val))?;
+    let text = svg.create_element("text").with_attributes(vec![
+        ("x", x.as_str()),
+        ("y", y.as_str()),
+        ("dominant-baseline", "hanging"),
+    ]);
+    if tooltip.is_none() && !val.contains('\n') {
+        text.write_text_content(BytesText::new(val))?;
+        return Ok(());
+    }
+    text.write_inner_content::<_, Error>(|svg| {
+        if let Some(t) = tooltip {
+            svg.create_element("title").write_text_content(BytesText::new(t))?;
+        }
+        if val.contains('\n') {
+            for (i, line) in val.split('\n').enumerate() {
+                let dy = if i == 0 { "0" } else { "1.2em" };
+                svg.create_element("tspan")
+                    .with_attributes(vec![("x", x.as_str()), ("dy", dy)])
+                    .write_text_content(BytesText::new(line))?;
+            }
+        } else {
+            svg.write_event(Event::Text(BytesText::new(val)))?;
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+pub(crate) fn write_info<W: std::io::Write>(
+    svg: &mut Writer<W>,
+    config: &Config,
+    x: u32,
+    y: u32,
+    info: &impl Info,
+) -> std::result::Result<(), Error> {
+    let tooltip = info.tooltip();
+    if let Some(link) = info.link() {
+        svg.create_element("a")
+            .with_attributes(vec![("xlink:href", link.as_str())])
+            .write_inner_content::<_, Error>(|svg| {
+                write_text(svg, config, x, y, info.label(), tooltip.as_deref())?;
+                Ok(())
+            })?;
+    } else {
+        write_text(svg, config, x, y, info.label(), tooltip.as_deref())?;
+    }
     Ok(())
 }
 
@@ -92,7 +202,7 @@ where
         let mut c = config.clone();
         c.set_metadata_first_offset(top_size);
         for info in iter {
-            write_text(svg, &c, 0, y, info.label())?;
+            write_info(svg, &c, 0, y, &info)?;
             y += info.block_count();
         }
     }
@@ -102,7 +212,7 @@ where
         let mut c = config.clone();
         c.set_metadata_second_offset(left_size);
         for info in iter {
-            write_text(svg, &c, x, 0, info.label())?;
+            write_info(svg, &c, x, 0, &info)?;
             x += info.block_count();
         }
     }
@@ -126,18 +236,20 @@ where
     for yval in data_source {
         let mut second = 0;
         for xval in yval {
-            if let Some(l) = xval.get_link() {
-                svg.create_element("a")
-                    .with_attributes(vec![
-                        ("xlink:href", l.link().as_str()),
-                        ("xlink:title", l.title().as_str()),
-                    ])
-                    .write_inner_content::<_, quick_xml::Error>(|svg| {
-                        write_rect(svg, &config, first, second, &xval)?;
-                        Ok(())
-                    })?;
-            } else {
-                write_rect(svg, &config, first, second, &xval)?;
+            if xval.is_visible() {
+                if let Some(l) = xval.get_link() {
+                    svg.create_element("a")
+                        .with_attributes(vec![
+                            ("xlink:href", l.link().as_str()),
+                            ("xlink:title", l.title().as_str()),
+                        ])
+                        .write_inner_content::<_, quick_xml::Error>(|svg| {
+                            write_rect(svg, config, first, second, &xval)?;
+                            Ok(())
+                        })?;
+                } else {
+                    write_rect(svg, config, first, second, &xval)?;
+                }
             }
             second += 1;
             if second > max_second {
@@ -149,6 +261,36 @@ where
     Ok((first, max_second))
 }
 
+/// Write the tiles surrounded by the metadata labels, returning the count of cells written
+/// on the first and second axis.
+pub(crate) fn write_metadata_tile<W, D, B, E, M, MIT, MIN>(
+    svg: &mut Writer<W>,
+    mut config: Config,
+    metadata: M,
+    data_source: D,
+    overlays: &[Overlay],
+) -> std::result::Result<(u32, u32), Error>
+where
+    D: Iterator<Item = B>,
+    B: Iterator<Item = E>,
+    E: Element,
+    W: Write,
+    M: Metadata<MIT, MIN>,
+    MIT: Iterator<Item = MIN>,
+    MIN: Info,
+{
+    let top_size = metadata.top_size();
+    let left_size = metadata.left_size();
+    let right_size = right_labels_size(&config, &metadata);
+    write_metadata(svg, &config, &metadata)?;
+    config.set_metadata_first_offset(top_size);
+    config.set_metadata_second_offset(left_size);
+    let (max_first, max_second) = write_tile(svg, data_source, &config)?;
+    write_overlays(svg, &config, overlays, max_second, right_size)?;
+    write_after_metadata(svg, &config, metadata, max_first, max_second)?;
+    Ok((max_first, max_second))
+}
+
 pub(crate) fn write_after_metadata<W, M, MIT, MIN>(
     svg: &mut Writer<W>,
     config: &Config,
@@ -168,7 +310,7 @@ where
         c.set_metadata_after_second_offset(max_second);
         for info in iter {
             let block = info.block_count();
-            write_text(svg, &c, 0, y, info.label())?;
+            write_info(svg, &c, 0, y, &info)?;
             y += block;
         }
     }
@@ -179,10 +321,243 @@ where
         c.set_metadata_after_first_offset(max_first);
         for info in iter {
             let block = info.block_count();
-            write_text(svg, &c, x, 0, info.label())?;
+            write_info(svg, &c, x, 0, &info)?;
             x += block;
         }
     }
 
     Ok(())
 }
+
+/// Space taken after the second axis by the `right` labels, measured from their text, the
+/// annotations are drawn after it.
+pub(crate) fn right_labels_size<M, MIT, MIN>(config: &Config, metadata: &M) -> u32
+where
+    M: Metadata<MIT, MIN>,
+    MIT: Iterator<Item = MIN>,
+    MIN: Info,
+{
+    let size = metadata
+        .right()
+        .into_iter()
+        .flatten()
+        .map(|info| text_size(config, info.label()))
+        .fold(0.0, f64::max);
+    if size > 0.0 {
+        // keep a small gap between the labels and what follows
+        size.ceil() as u32 + 4
+    } else {
+        0
+    }
+}
+
+/// Space taken across the first axis by a text written along it, one line per `\n`.
+pub(crate) fn text_size(config: &Config, text: &str) -> f64 {
+    let lines = text.split('\n');
+    if config.first_horizontal() {
+        lines.count() as f64 * TEXT_HEIGHT
+    } else {
+        lines.map(|line| line.chars().count()).max().unwrap_or(0) as f64 * TEXT_WIDTH
+    }
+}
+
+#[cfg(feature = "year_line")]
+/// Space taken after the second axis by the lanes of the annotations of the overlays.
+pub(crate) fn annotations_size(config: &Config, overlays: &[Overlay]) -> u32 {
+    let annotations = overlays
+        .iter()
+        .filter_map(|overlay| match overlay {
+            Overlay::Annotation(annotation) => Some(annotation),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let lanes = annotation_lanes(&annotations, |text| text_size(config, text));
+    annotations
+        .iter()
+        .zip(lanes)
+        .map(|(annotation, lane)| lane + ANNOTATION_MARK + text_size(config, annotation.get_text()))
+        .fold(0.0, f64::max)
+        .ceil() as u32
+}
+
+/// Write the overlays, the annotations are placed after the second axis leaving `after`
+/// space for the labels drawn there.
+pub(crate) fn write_overlays<W: Write>(
+    svg: &mut Writer<W>,
+    config: &Config,
+    overlays: &[Overlay],
+    max_second: u32,
+    after: u32,
+) -> std::result::Result<(), Error> {
+    let mut annotations = Vec::new();
+    for overlay in overlays {
+        match overlay {
+            Overlay::Outline(outline) => write_outline(svg, config, outline)?,
+            Overlay::Annotation(annotation) => annotations.push(annotation),
+            Overlay::Bar(bar) => write_bar(svg, config, bar, max_second)?,
+        }
+    }
+    write_annotations(svg, config, &annotations, max_second, after)?;
+    Ok(())
+}
+
+/// Draw the bar as a rounded rectangle for each first it covers, half as thick as the tiles.
+fn write_bar<W: Write>(
+    svg: &mut Writer<W>,
+    config: &Config,
+    bar: &Bar,
+    max_second: u32,
+) -> std::result::Result<(), Error> {
+    let thickness = config.first_size() / 2.0;
+    let style = element_style(config, bar.element());
+    let link = bar.element().get_link();
+    let mut rects = Vec::new();
+    for (first, start, end) in bar.segments(max_second) {
+        let from = config.second_coordinate(start);
+        let length = config.second_coordinate(end) + config.second_size() - from;
+        let (x, y) = config.point(config.first_coordinate(first) + thickness / 2.0, from);
+        let (width, height) = if config.first_horizontal() {
+            (thickness, length)
+        } else {
+            (length, thickness)
+        };
+        rects.push((x, y, width, height));
+    }
+    let write_rects = |svg: &mut Writer<W>| -> std::result::Result<(), Error> {
+        let radius = format!("{}", thickness / 2.0);
+        for (x, y, width, height) in &rects {
+            let (x, y, width, height) = (
+                format!("{}", x),
+                format!("{}", y),
+                format!("{}", width),
+                format!("{}", height),
+            );
+            svg.create_element("rect")
+                .with_attributes(vec![
+                    ("x", x.as_str()),
+                    ("y", y.as_str()),
+                    ("rx", radius.as_str()),
+                    ("ry", radius.as_str()),
+                    ("width", width.as_str()),
+                    ("height", height.as_str()),
+                    ("style", style.as_str()),
+                ])
+                .write_empty()?;
+        }
+        Ok(())
+    };
+    if let Some(l) = link {
+        svg.create_element("a")
+            .with_attributes(vec![
+                ("xlink:href", l.link().as_str()),
+                ("xlink:title", l.title().as_str()),
+            ])
+            .write_inner_content::<_, quick_xml::Error>(|svg| write_rects(svg))?;
+    } else {
+        write_rects(svg)?;
+    }
+    Ok(())
+}
+
+fn write_outline<W: Write>(svg: &mut Writer<W>, config: &Config, outline: &Outline) -> std::result::Result<(), Error> {
+    let mut path = String::new();
+    for points in outline.loops() {
+        // each corner takes the edges of the cells along its two sides, the edge closing the
+        // cells before a group boundary differs from the one opening the cells after it
+        for (i, &(first, second)) in points.iter().enumerate() {
+            let before = points[(i + points.len() - 1) % points.len()];
+            let after = points[(i + 1) % points.len()];
+            let (along_second, along_first) = if before.0 == first {
+                (before, after)
+            } else {
+                (after, before)
+            };
+            let closing = (
+                first > 0 && outline.contains((first - 1, second.min(along_second.1))),
+                second > 0 && outline.contains((first.min(along_first.0), second - 1)),
+            );
+            let (x, y) = config.edge(first, second, closing);
+            let command = if i == 0 { "M" } else { "L" };
+            path.push_str(&format!("{}{} {} ", command, x, y));
+        }
+        path.push('Z');
+    }
+    let style = format!(
+        "fill:none;stroke-width:{};stroke:{}",
+        outline.stroke_width(),
+        outline.color().to_hex_string()
+    );
+    svg.create_element("path")
+        .with_attributes(vec![("d", path.as_str()), ("style", style.as_str())])
+        .write_empty()?;
+    if let (Some(label), Some((first, second))) = (outline.get_label(), outline.first_cell()) {
+        let (x, y) = config.edge(first, second, (false, false));
+        write_free_text(svg, x, y, "text-after-edge", label)?;
+    }
+    Ok(())
+}
+
+fn write_annotations<W: Write>(
+    svg: &mut Writer<W>,
+    config: &Config,
+    annotations: &[&Annotation],
+    max_second: u32,
+    after: u32,
+) -> std::result::Result<(), Error> {
+    let lanes = annotation_lanes(annotations, |text| text_size(config, text));
+    let band = config.second_coordinate(max_second) + after as f64;
+    for (annotation, lane) in annotations.iter().zip(lanes) {
+        let (start, end) = annotation.first_range();
+        let from = config.first_coordinate(start);
+        let to = config.first_coordinate(end) + config.first_size();
+        let across = band + lane;
+        let points = match annotation.get_style() {
+            AnnotationStyle::Bracket => vec![(from, across), (from, across + 5.0), (to, across + 5.0), (to, across)],
+            AnnotationStyle::Marker => {
+                let middle = from + config.first_size() / 2.0;
+                vec![
+                    (middle, across),
+                    (middle - 4.0, across + 6.0),
+                    (middle + 4.0, across + 6.0),
+                    (middle, across),
+                ]
+            }
+        };
+        let mut path = String::new();
+        for (i, (first, second)) in points.into_iter().enumerate() {
+            let (x, y) = config.point(first, second);
+            let command = if i == 0 { "M" } else { "L" };
+            path.push_str(&format!("{}{} {} ", command, x, y));
+        }
+        let color = annotation.get_color().to_hex_string();
+        let style = match annotation.get_style() {
+            AnnotationStyle::Bracket => format!("fill:none;stroke-width:1;stroke:{}", color),
+            AnnotationStyle::Marker => format!("fill:{};stroke:none", color),
+        };
+        svg.create_element("path")
+            .with_attributes(vec![("d", path.trim_end()), ("style", style.as_str())])
+            .write_empty()?;
+        let (x, y) = config.point(from, across + ANNOTATION_MARK);
+        write_free_text(svg, x, y, "hanging", annotation.get_text())?;
+    }
+    Ok(())
+}
+
+/// Text placed at an absolute position, outside of the cell grid.
+pub(crate) fn write_free_text<W: Write>(
+    svg: &mut Writer<W>,
+    x: f64,
+    y: f64,
+    baseline: &str,
+    val: &str,
+) -> std::result::Result<(), Error> {
+    let (x, y) = (format!("{}", x), format!("{}", y));
+    svg.create_element("text")
+        .with_attributes(vec![
+            ("x", x.as_str()),
+            ("y", y.as_str()),
+            ("dominant-baseline", baseline),
+        ])
+        .write_text_content(BytesText::new(val))?;
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use crate::{
+    metadata_tile,
+    year::{CalendarNames, YearInfo},
+    Config, Element, EventTime, Metadata, Mode, Scale,
+};
+use builder_pattern::Builder;
+use chrono::{Datelike, Locale, Timelike, Weekday};
+use std::io::Write;
+use std::marker::PhantomData;
+
+/// Hours grouped under each label on top of the punch card.
+const HOURS_LABEL_SPAN: u32 = 3;
+
+#[derive(Builder)]
+pub struct PunchCardOptions {
+    /// Day of the first row.
+    #[default(Weekday::Sun)]
+    #[public]
+    week_start: Weekday,
+    /// Locale used for the weekday names.
+    #[default(Locale::en_US)]
+    #[public]
+    locale: Locale,
+}
+
+/// Count of events for each weekday and hour, mapped to elements by a scale.
+pub struct PunchCard<E, S> {
+    /// Counts by day from Monday and hour.
+    counts: [[u32; 24]; 7],
+    scale: S,
+    element: PhantomData<E>,
+}
+
+impl<E: Element, S: Scale<E>> PunchCard<E, S> {
+    pub fn new<I, T>(events: I, scale: S) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: EventTime,
+    {
+        let mut counts = [[0; 24]; 7];
+        for event in events {
+            let time = event.event_time();
+            counts[time.weekday().num_days_from_monday() as usize][time.hour() as usize] += 1;
+        }
+        Self {
+            counts,
+            scale,
+            element: PhantomData,
+        }
+    }
+
+    pub fn count(&self, weekday: Weekday, hour: u32) -> u32 {
+        self.counts[weekday.num_days_from_monday() as usize]
+            .get(hour as usize)
+            .cloned()
+            .unwrap_or(0)
+    }
+
+    /// Count of the busiest weekday and hour.
+    pub fn max_count(&self) -> u32 {
+        self.counts.iter().flatten().cloned().max().unwrap_or(0)
+    }
+}
+
+struct PunchCardMetadata {
+    names: CalendarNames,
+}
+
+impl Metadata<std::vec::IntoIter<YearInfo>, YearInfo> for PunchCardMetadata {
+    fn left_size(&self) -> u32 {
+        35
+    }
+
+    fn top_size(&self) -> u32 {
+        20
+    }
+
+    fn left(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        Some(
+            (0..7)
+                .map(|day| YearInfo::new(&self.names.weekday_name(day), 1))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    fn top(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        Some(YearInfo::hours(HOURS_LABEL_SPAN, |hour| format!("{}", hour)))
+    }
+
+    fn right(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+
+    fn bottom(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+}
+
+/// Render the events by weekday and hour, with a row for each day of the week and a column
+/// for each hour of the day.
+pub fn punch_card<W, E, S>(
+    source: PunchCard<E, S>,
+    output: W,
+    config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    E: Element,
+    S: Scale<E>,
+    W: Write,
+{
+    punch_card_with_options(source, output, config, PunchCardOptions::new().build())
+}
+
+pub fn punch_card_with_options<W, E, S>(
+    source: PunchCard<E, S>,
+    output: W,
+    mut config: Config,
+    options: PunchCardOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    E: Element,
+    S: Scale<E>,
+    W: Write,
+{
+    config.default_mode(Mode::RowColumn);
+    let start = options.week_start.num_days_from_monday() as usize;
+    let rows = (0..7)
+        .map(|day| {
+            source.counts[(start + day) % 7]
+                .iter()
+                .map(|count| source.scale.element(*count as f64))
+                .collect::<Vec<_>>()
+                .into_iter()
+        })
+        .collect::<Vec<_>>();
+    let metadata = PunchCardMetadata {
+        names: CalendarNames::new(options.week_start, options.locale),
+    };
+    metadata_tile(config, metadata, rows.into_iter(), output)?;
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use crate::{Element, ElementLink, Rgb};
+
+/// Map a value to the element that represents it.
+pub trait Scale<E: Element> {
+    fn element(&self, value: f64) -> E;
+}
+
+impl<E: Element, F: Fn(f64) -> E> Scale<E> for F {
+    fn element(&self, value: f64) -> E {
+        self(value)
+    }
+}
+
+/// Scale of colors by value, zero or less gets the first color.
+#[derive(Clone)]
+pub struct ColorScale {
+    colors: Vec<Rgb>,
+    max: f64,
+}
+
+impl ColorScale {
+    pub fn new(colors: Vec<Rgb>, max: f64) -> Self {
+        Self { colors, max }
+    }
+
+    /// Shades of green from light grey for no value, like the contribution calendars.
+    pub fn greens(max: f64) -> Self {
+        Self::from_hex(&["#ebedf0", "#9be9a8", "#40c463", "#30a14e", "#216e39"], max)
+    }
+
+    /// Shades of blue from light grey for no value.
+    pub fn blues(max: f64) -> Self {
+        Self::from_hex(&["#ebedf0", "#c6dbef", "#6baed6", "#2171b5", "#08306b"], max)
+    }
+
+    /// Shades of orange and red from light grey for no value.
+    pub fn heat(max: f64) -> Self {
+        Self::from_hex(&["#ebedf0", "#fed976", "#fd8d3c", "#e31a1c", "#800026"], max)
+    }
+
+    fn from_hex(colors: &[&str], max: f64) -> Self {
+        Self::new(colors.iter().map(|c| Rgb::from_hex_str(c).unwrap()).collect(), max)
+    }
+
+    pub fn color(&self, value: f64) -> Rgb {
+        let levels = self.colors.len();
+        if levels == 0 {
+            return Rgb::new(255.0, 255.0, 255.0, None);
+        }
+        if value <= 0.0 || levels == 1 {
+            return self.colors[0].clone();
+        }
+        let ratio = (value / self.max).min(1.0);
+        let index = ((ratio * (levels - 1) as f64).ceil() as usize).clamp(1, levels - 1);
+        self.colors[index].clone()
+    }
+}
+
+impl Scale<ScaledElement> for ColorScale {
+    fn element(&self, value: f64) -> ScaledElement {
+        ScaledElement {
+            color: self.color(value),
+            value,
+            size: 1.0,
+        }
+    }
+}
+
+/// Scale of tile sizes by value, up to a full tile at `max`.
+#[derive(Clone)]
+pub struct SizeScale {
+    color: Rgb,
+    max: f64,
+}
+
+impl SizeScale {
+    pub fn new(color: Rgb, max: f64) -> Self {
+        Self { color, max }
+    }
+
+    pub fn size(&self, value: f64) -> f64 {
+        if value <= 0.0 || self.max <= 0.0 {
+            0.0
+        } else {
+            (value / self.max).min(1.0).sqrt()
+        }
+    }
+}
+
+impl Scale<ScaledElement> for SizeScale {
+    fn element(&self, value: f64) -> ScaledElement {
+        ScaledElement {
+            color: self.color.clone(),
+            value,
+            size: self.size(value),
+        }
+    }
+}
+
+/// Element produced by a `ColorScale` or a `SizeScale`, keeping the value it represents.
+#[derive(Clone)]
+pub struct ScaledElement {
+    color: Rgb,
+    value: f64,
+    size: f64,
+}
+
+impl ScaledElement {
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+impl Element for ScaledElement {
+    fn get_color(&self) -> Rgb {
+        self.color.clone()
+    }
+
+    fn get_border_color(&self) -> Rgb {
+        self.color.clone()
+    }
+
+    fn get_link(&self) -> Option<Box<dyn ElementLink>> {
+        None
+    }
+
+    fn is_visible(&self) -> bool {
+        self.size > 0.0
+    }
+
+    fn scale(&self) -> f64 {
+        self.size
+    }
+
+    fn value(&self) -> Option<f64> {
+        Some(self.value)
+    }
+}
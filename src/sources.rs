@@ -0,0 +1,138 @@
+use crate::{DailyValues, DateDataSource, Element, MultiDateDataSource, Scale};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+impl<E: Element, F: Fn(NaiveDate) -> E> DateDataSource<E> for F {
+    fn get_element(&self, data: NaiveDate) -> E {
+        self(data)
+    }
+}
+
+/// Elements by date, the second element is used for the dates missing in the map.
+impl<E: Element + Clone> DateDataSource<E> for (HashMap<NaiveDate, E>, E) {
+    fn get_element(&self, data: NaiveDate) -> E {
+        self.0.get(&data).unwrap_or(&self.1).clone()
+    }
+}
+
+/// Elements by date, the second element is used for the dates missing in the map.
+impl<E: Element + Clone> DateDataSource<E> for (BTreeMap<NaiveDate, E>, E) {
+    fn get_element(&self, data: NaiveDate) -> E {
+        self.0.get(&data).unwrap_or(&self.1).clone()
+    }
+}
+
+/// Labeled series, each with its own data source.
+impl<E: Element, D: DateDataSource<E>> MultiDateDataSource<E> for Vec<(String, D)> {
+    fn labels(&self) -> Vec<String> {
+        self.iter().map(|(label, _)| label.clone()).collect()
+    }
+
+    fn get_element(&self, series: usize, data: NaiveDate) -> E {
+        self[series].1.get_element(data)
+    }
+}
+
+/// Anything that happened on a day.
+pub trait EventDate {
+    fn event_date(&self) -> NaiveDate;
+}
+
+impl EventDate for NaiveDate {
+    fn event_date(&self) -> NaiveDate {
+        *self
+    }
+}
+
+impl EventDate for NaiveDateTime {
+    fn event_date(&self) -> NaiveDate {
+        self.date()
+    }
+}
+
+/// The local date in the timezone of the date time.
+impl<Tz: TimeZone> EventDate for DateTime<Tz> {
+    fn event_date(&self) -> NaiveDate {
+        self.date_naive()
+    }
+}
+
+/// Anything that happened at a time of the day.
+pub trait EventTime {
+    fn event_time(&self) -> NaiveDateTime;
+}
+
+impl EventTime for NaiveDateTime {
+    fn event_time(&self) -> NaiveDateTime {
+        *self
+    }
+}
+
+/// The local time in the timezone of the date time.
+impl<Tz: TimeZone> EventTime for DateTime<Tz> {
+    fn event_time(&self) -> NaiveDateTime {
+        self.naive_local()
+    }
+}
+
+/// Count of events for each day, mapped to elements by a scale.
+pub struct EventCounts<E, S> {
+    days: DailyValues<E, S>,
+}
+
+impl<E: Element, S: Scale<E>> EventCounts<E, S> {
+    pub fn new<I, T>(events: I, scale: S) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: EventDate,
+    {
+        let mut counts = BTreeMap::new();
+        for event in events {
+            *counts.entry(event.event_date()).or_insert(0.0) += 1.0;
+        }
+        Self {
+            days: DailyValues::new(counts, scale),
+        }
+    }
+
+    pub fn count(&self, date: NaiveDate) -> u32 {
+        self.days.value(date).unwrap_or(0.0) as u32
+    }
+
+    /// Count of the busiest day, zero without events.
+    pub fn max_count(&self) -> u32 {
+        self.days.max_value().unwrap_or(0.0) as u32
+    }
+}
+
+impl<E: Element, S: Scale<E>> DateDataSource<E> for EventCounts<E, S> {
+    fn get_element(&self, data: NaiveDate) -> E {
+        self.days.get_element(data)
+    }
+}
+
+/// Start dates of the events of an iCalendar content, like an exported holiday calendar.
+pub fn ical_dates(content: &str) -> std::result::Result<BTreeSet<NaiveDate>, Box<dyn std::error::Error>> {
+    // continuation lines start with a space or a tab
+    let unfolded = content.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+    let mut dates = BTreeSet::new();
+    let mut in_event = false;
+    for line in unfolded.lines() {
+        let (name, value) = match line.split_once(':') {
+            Some((name, value)) => (name.to_ascii_uppercase(), value.trim()),
+            None => continue,
+        };
+        match (name.as_str(), value) {
+            ("BEGIN", "VEVENT") => in_event = true,
+            ("END", "VEVENT") => in_event = false,
+            _ if in_event && (name == "DTSTART" || name.starts_with("DTSTART;")) => {
+                let date = value
+                    .get(..8)
+                    .ok_or_else(|| format!("invalid DTSTART value {}", value))?;
+                dates.insert(NaiveDate::parse_from_str(date, "%Y%m%d")?);
+            }
+            _ => {}
+        }
+    }
+    Ok(dates)
+}
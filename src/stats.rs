@@ -0,0 +1,103 @@
+use crate::{DateDataSource, Element};
+use chrono::NaiveDate;
+
+/// Summary of the values of the days of a range, the days without a positive value are not
+/// active and break the streaks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DateStats {
+    total: f64,
+    active_days: u32,
+    longest_streak: u32,
+    current_streak: u32,
+    busiest_day: Option<(NaiveDate, f64)>,
+}
+
+impl DateStats {
+    /// Compute the statistics of the days from `start` to `end` inclusive, from the values of
+    /// the elements of the data source.
+    pub fn new<D, E>(data_source: &D, start: NaiveDate, end: NaiveDate) -> Self
+    where
+        D: DateDataSource<E> + ?Sized,
+        E: Element,
+    {
+        Self::from_values(
+            start
+                .iter_days()
+                .take_while(|day| day <= &end)
+                .map(|day| (day, data_source.get_element(day).value().unwrap_or(0.0))),
+        )
+    }
+
+    /// Compute the statistics of a sequence of consecutive days with their values.
+    pub fn from_values(values: impl IntoIterator<Item = (NaiveDate, f64)>) -> Self {
+        let mut stats = Self {
+            total: 0.0,
+            active_days: 0,
+            longest_streak: 0,
+            current_streak: 0,
+            busiest_day: None,
+        };
+        let mut streak = 0;
+        // streak interrupted by the last day, still current as the last day may not be over
+        let mut interrupted = 0;
+        for (day, value) in values {
+            if value > 0.0 {
+                stats.total += value;
+                stats.active_days += 1;
+                streak += 1;
+                stats.longest_streak = stats.longest_streak.max(streak);
+                if stats.busiest_day.map(|(_, max)| value > max).unwrap_or(true) {
+                    stats.busiest_day = Some((day, value));
+                }
+            } else {
+                interrupted = streak;
+                streak = 0;
+            }
+        }
+        stats.current_streak = if streak > 0 { streak } else { interrupted };
+        stats
+    }
+
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    pub fn active_days(&self) -> u32 {
+        self.active_days
+    }
+
+    pub fn longest_streak(&self) -> u32 {
+        self.longest_streak
+    }
+
+    /// Streak ending on the last day, or on the day before when the last day is not active.
+    pub fn current_streak(&self) -> u32 {
+        self.current_streak
+    }
+
+    /// Day with the highest value, the first one on ties.
+    pub fn busiest_day(&self) -> Option<(NaiveDate, f64)> {
+        self.busiest_day
+    }
+
+    /// Text of the statistics, one per line.
+    pub fn summary(&self) -> String {
+        let mut lines = vec![
+            format!("Total: {}", self.total),
+            format!("Longest streak: {}", days(self.longest_streak)),
+            format!("Current streak: {}", days(self.current_streak)),
+        ];
+        if let Some((day, value)) = self.busiest_day {
+            lines.push(format!("Busiest day: {} ({})", day, value));
+        }
+        lines.join("\n")
+    }
+}
+
+fn days(count: u32) -> String {
+    if count == 1 {
+        "1 day".to_owned()
+    } else {
+        format!("{} days", count)
+    }
+}
@@ -0,0 +1,142 @@
+use crate::{metadata_tile, year::YearInfo, Config, DateDataSource, Element, Metadata, Mode};
+use chrono::{Datelike, IsoWeek, NaiveDate, Weekday};
+use std::io::Write;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+use std::rc::Rc;
+
+/// Weeks grouped under each week number label.
+const WEEKS_LABEL_SPAN: u32 = 4;
+
+pub trait WeekDataSource<E: Element> {
+    fn get_element(&self, week: IsoWeek) -> E;
+}
+
+impl<E: Element, F: Fn(IsoWeek) -> E> WeekDataSource<E> for F {
+    fn get_element(&self, week: IsoWeek) -> E {
+        self(week)
+    }
+}
+
+/// Week data source combining the elements of the seven days of each ISO week, from Monday to
+/// Sunday, of a date data source.
+pub struct WeeklyAggregate<D, X, F> {
+    days: D,
+    reduce: F,
+    element: PhantomData<X>,
+}
+
+impl<D, X, F> WeeklyAggregate<D, X, F> {
+    pub fn new(days: D, reduce: F) -> Self {
+        Self {
+            days,
+            reduce,
+            element: PhantomData,
+        }
+    }
+}
+
+impl<D, X, F, E> WeekDataSource<E> for WeeklyAggregate<D, X, F>
+where
+    D: DateDataSource<X>,
+    X: Element,
+    F: Fn(Vec<X>) -> E,
+    E: Element,
+{
+    fn get_element(&self, week: IsoWeek) -> E {
+        let days = week_days(week.year(), week.week())
+            .map(|days| days.map(|day| self.days.get_element(day)).collect())
+            .unwrap_or_default();
+        (self.reduce)(days)
+    }
+}
+
+/// The days of the ISO week `week` of the ISO year `year`.
+fn week_days(year: i32, week: u32) -> Option<impl Iterator<Item = NaiveDate>> {
+    let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+    Some(monday.iter_days().take(7))
+}
+
+/// Number of ISO weeks of the ISO year, 52 or 53.
+fn iso_weeks(year: i32) -> u32 {
+    if NaiveDate::from_isoywd_opt(year, 53, Weekday::Mon).is_some() {
+        53
+    } else {
+        52
+    }
+}
+
+struct WeeksMetadata {
+    years: RangeInclusive<i32>,
+    weeks: u32,
+}
+
+impl Metadata<std::vec::IntoIter<YearInfo>, YearInfo> for WeeksMetadata {
+    fn left_size(&self) -> u32 {
+        40
+    }
+
+    fn top_size(&self) -> u32 {
+        20
+    }
+
+    fn left(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        Some(
+            self.years
+                .clone()
+                .map(|year| YearInfo::new(&format!("{}", year), 1))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    fn top(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        Some(
+            (1..=self.weeks)
+                .step_by(WEEKS_LABEL_SPAN as usize)
+                .map(|week| YearInfo::new(&format!("{}", week), WEEKS_LABEL_SPAN.min(self.weeks + 1 - week)))
+                .collect::<Vec<_>>()
+                .into_iter(),
+        )
+    }
+
+    fn right(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+
+    fn bottom(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        None
+    }
+}
+
+/// Render a tile for each ISO week, with a row for each ISO year of the range.
+pub fn weeks_line<W, D, E>(
+    years: RangeInclusive<i32>,
+    data_source: D,
+    output: W,
+    mut config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: WeekDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    if years.is_empty() {
+        return Err(format!("empty range of years {:?}", years).into());
+    }
+    config.default_mode(Mode::RowColumn);
+    let metadata = WeeksMetadata {
+        years: years.clone(),
+        weeks: years.clone().map(iso_weeks).max().unwrap_or(52),
+    };
+    let data_source = Rc::new(data_source);
+    let data = years.map(move |year| {
+        let data_source = data_source.clone();
+        (1..=iso_weeks(year)).filter_map(move |week| {
+            let monday = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)?;
+            Some(data_source.get_element(monday.iso_week()))
+        })
+    });
+    metadata_tile(config, metadata, data, output)?;
+    Ok(())
+}
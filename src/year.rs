@@ -1,63 +1,438 @@
-use crate::{metadata_tile, Config, Element, ElementLink, Info, Metadata, Mode};
-use chrono::{Datelike, NaiveDate};
-use colorsys::Rgb;
+use crate::{
+    metadata_tile_with_overlays,
+    pieces::{
+        annotations_size, base_doc, right_labels_size, text_size, write_free_text, write_metadata_tile, write_text,
+    },
+    Annotation, Bar, Config, DateStats, Element, ElementLink, Info, Metadata, Mode, Outline, Overlay,
+};
+use builder_pattern::Builder;
+use chrono::{Datelike, Days, Locale, NaiveDate, Weekday};
+use colorsys::{ColorAlpha, Rgb};
+use quick_xml::Writer;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::io::Write;
+use std::ops::RangeInclusive;
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+/// Space for the title of a calendar block, like a month or a year.
+pub(crate) const TITLE_SIZE: u32 = 20;
+/// Space between the calendar blocks.
+pub(crate) const BLOCK_SPACING: u32 = 20;
+/// Width reserved for each character of the series labels.
+const SERIES_CHAR_WIDTH: u32 = 8;
 
 pub trait DateDataSource<E: Element> {
     fn get_element(&self, data: NaiveDate) -> E;
 }
 
-struct Year<E: Element> {
-    date: Box<dyn Iterator<Item = NaiveDate>>,
+/// Several date series drawn in the same calendar, like habits or repositories.
+pub trait MultiDateDataSource<E: Element> {
+    /// Label of each series, in the order they are drawn.
+    fn labels(&self) -> Vec<String>;
+    fn get_element(&self, series: usize, data: NaiveDate) -> E;
+}
+
+/// A single series of a multi series data source.
+struct Series<E> {
+    source: Rc<dyn MultiDateDataSource<E>>,
+    index: usize,
+}
+
+impl<E: Element> DateDataSource<E> for Series<E> {
+    fn get_element(&self, data: NaiveDate) -> E {
+        self.source.get_element(self.index, data)
+    }
+}
+
+/// Rendering of the days, shared by all the calendar views.
+#[derive(Builder, Clone)]
+pub struct DayOptions {
+    /// First day of each week.
+    #[default(Weekday::Sun)]
+    #[public]
+    week_start: Weekday,
+    /// Locale used for the weekday and month names.
+    #[default(Locale::en_US)]
+    #[public]
+    locale: Locale,
+    /// Rendering of the days in the first and last week outside of the range.
+    #[default(Padding::White)]
+    #[public]
+    padding: Padding,
+    /// The current day, used by `today_outline` and `future_color`.
+    #[default(None)]
+    #[into]
+    #[public]
+    today: Option<NaiveDate>,
+    /// Days drawn with `holiday_color`.
+    #[default(BTreeSet::new())]
+    #[into]
+    #[public]
+    holidays: BTreeSet<NaiveDate>,
+    /// Color of the outline traced around Saturdays and Sundays.
+    #[default(None)]
+    #[into]
+    #[public]
+    weekend_color: Option<Rgb>,
+    /// Color of the outline traced around the holidays, over the weekend color.
+    #[default(None)]
+    #[into]
+    #[public]
+    holiday_color: Option<Rgb>,
+    /// Color of the outline traced around today.
+    #[default(None)]
+    #[into]
+    #[public]
+    today_outline: Option<Rgb>,
+    /// Color mixed into the days after today to grey them out.
+    #[default(None)]
+    #[into]
+    #[public]
+    future_color: Option<Rgb>,
+    /// Link of the days, with the placeholders `{yyyy-mm-dd}`, `{date}`, `{weekday}` and `{value}`.
+    #[default(None)]
+    #[into]
+    #[public]
+    link_template: Option<String>,
+    /// Title of the days linked by `link_template`, with the same placeholders.
+    #[default(None)]
+    #[into]
+    #[public]
+    title_template: Option<String>,
+}
+
+#[derive(Builder)]
+pub struct YearOptions {
+    #[default(DayOptions::new().build())]
+    #[public]
+    days: DayOptions,
+    /// Color of the outline traced around each month, no outline if not set.
+    #[default(None)]
+    #[into]
+    #[public]
+    month_outline: Option<Rgb>,
+    /// Annotations of date ranges, drawn below the weeks.
+    #[default(Vec::new())]
+    #[public]
+    annotations: Vec<Annotation<NaiveDate>>,
+    /// Lay out the year on the ISO weeks, starting on Monday whatever the week start.
+    #[default(false)]
+    #[public]
+    iso_weeks: bool,
+    /// Labels drawn along the weeks.
+    #[default(WeekLabels::Months)]
+    #[public]
+    week_labels: WeekLabels,
+    /// Show the statistics of the days, like total and streaks.
+    #[default(false)]
+    #[public]
+    stats_footer: bool,
+    /// Date intervals drawn as bars over the days, like on-call rotations.
+    #[default(Vec::new())]
+    #[public]
+    intervals: Vec<Bar<NaiveDate>>,
+}
+
+/// Labels drawn along the weeks of the year views.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum WeekLabels {
+    /// The month names, on the side of the months first week.
+    Months,
+    /// The ISO week number of each week.
+    Weeks,
+    /// The month names, and the ISO week numbers on the other side of the days.
+    Both,
+}
+
+impl DayOptions {
+    pub(crate) fn week_start(&self) -> Weekday {
+        self.week_start
+    }
+
+    pub(crate) fn locale(&self) -> Locale {
+        self.locale
+    }
+
+    /// Highlight color of the date, the holidays over the weekends.
+    fn highlight(&self, date: NaiveDate) -> Option<Rgb> {
+        let holiday = self.holidays.contains(&date);
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        self.holiday_color
+            .clone()
+            .filter(|_| holiday)
+            .or_else(|| self.weekend_color.clone().filter(|_| weekend))
+    }
+
+    /// Outlines of each highlighted day and of today, leaving the data colors untouched.
+    pub(crate) fn day_overlays(&self, layout: &DateLayout) -> Vec<Overlay> {
+        let mut overlays = layout
+            .days()
+            .filter_map(|date| {
+                let color = self.highlight(date)?;
+                Some(Overlay::Outline(Outline::new(vec![layout.cell(date)], color).width(1)))
+            })
+            .collect::<Vec<_>>();
+        let today = self.today.filter(|today| layout.contains(*today));
+        if let (Some(today), Some(color)) = (today, self.today_outline.clone()) {
+            overlays.push(Overlay::Outline(Outline::new(vec![layout.cell(today)], color).width(2)));
+        }
+        overlays
+    }
+}
+
+impl YearOptions {
+    fn week_start(&self) -> Weekday {
+        if self.iso_weeks {
+            Weekday::Mon
+        } else {
+            self.days.week_start
+        }
+    }
+
+    /// Layout of the days of the year, following the ISO weeks if requested.
+    fn year_layout(&self, year: i32) -> std::result::Result<DateLayout, String> {
+        let layout = if self.iso_weeks {
+            DateLayout::iso_year(year)
+        } else {
+            DateLayout::year(year, self.days.week_start)
+        };
+        layout.ok_or_else(|| format!("invalid year {}", year))
+    }
+}
+
+/// Number of days from the week start to the weekday.
+fn days_from(weekday: Weekday, week_start: Weekday) -> u32 {
+    (weekday.num_days_from_monday() + 7 - week_start.num_days_from_monday()) % 7
+}
+
+/// Position of the days of a date range in a grid of weeks.
+#[derive(Clone)]
+pub(crate) struct DateLayout {
+    first_day: NaiveDate,
+    start: NaiveDate,
+    end: NaiveDate,
+}
+
+impl DateLayout {
+    /// None if some days of the padded weeks are out of the supported dates.
+    pub(crate) fn new(start: NaiveDate, end: NaiveDate, week_start: Weekday) -> Option<Self> {
+        let pad = days_from(start.weekday(), week_start);
+        let layout = Self {
+            first_day: start.checked_sub_days(Days::new(pad as u64))?,
+            start,
+            end,
+        };
+        let days = layout.weeks() as u64 * 7 - 1;
+        layout.first_day.checked_add_days(Days::new(days))?;
+        Some(layout)
+    }
+
+    fn year(year: i32, week_start: Weekday) -> Option<Self> {
+        let start = NaiveDate::from_ymd_opt(year, 1, 1)?;
+        let end = NaiveDate::from_ymd_opt(year, 12, 31)?;
+        Self::new(start, end, week_start)
+    }
+
+    /// All the days of the ISO weeks of the ISO year.
+    fn iso_year(year: i32) -> Option<Self> {
+        let start = NaiveDate::from_isoywd_opt(year, 1, Weekday::Mon)?;
+        let next = NaiveDate::from_isoywd_opt(year.checked_add(1)?, 1, Weekday::Mon)?;
+        Self::new(start, next.pred_opt()?, Weekday::Mon)
+    }
+
+    fn weeks(&self) -> u32 {
+        self.cell(self.end).0 + 1
+    }
+
+    /// ISO week number of each week, the week of its Monday.
+    fn week_numbers(&self) -> Vec<u32> {
+        let monday = days_from(Weekday::Mon, self.first_day.weekday());
+        (0..self.weeks())
+            .filter_map(|week| self.date(week, monday))
+            .map(|date| date.iso_week().week())
+            .collect()
+    }
+
+    /// Cell of the date, dates outside of the layout are moved to the closest day.
+    fn cell(&self, date: NaiveDate) -> (u32, u32) {
+        let date = date.clamp(self.start, self.end);
+        let days = (date - self.first_day).num_days() as u32;
+        (days / 7, days % 7)
+    }
+
+    fn date(&self, week: u32, day: u32) -> Option<NaiveDate> {
+        self.first_day.checked_add_days(Days::new((week * 7 + day) as u64))
+    }
+
+    fn contains(&self, date: NaiveDate) -> bool {
+        self.start <= date && date <= self.end
+    }
+
+    fn days(&self) -> impl Iterator<Item = NaiveDate> + '_ {
+        self.start.iter_days().take_while(|d| d <= &self.end)
+    }
+
+    /// Month and week of each 1st of the month, including the partial first month if it fits.
+    fn month_starts(&self) -> Vec<(u32, u32)> {
+        let mut starts = self
+            .days()
+            .filter(|d| d.day() == 1)
+            .map(|d| (d.month(), self.cell(d).0))
+            .collect::<Vec<_>>();
+        if self.start.day() != 1 && starts.first().map(|(_, week)| *week >= 2).unwrap_or(true) {
+            starts.insert(0, (self.start.month(), 0));
+        }
+        starts
+    }
+
+    fn month_outlines(&self, color: &Rgb) -> Vec<Overlay> {
+        let mut months: Vec<Vec<(u32, u32)>> = Vec::new();
+        let mut current = None;
+        for day in self.days() {
+            if current != Some(day.month()) {
+                current = Some(day.month());
+                months.push(Vec::new());
+            }
+            if let Some(cells) = months.last_mut() {
+                cells.push(self.cell(day));
+            }
+        }
+        months
+            .into_iter()
+            .map(|cells| Overlay::Outline(Outline::new(cells, color.clone())))
+            .collect()
+    }
+}
+
+/// How the days of the padded weeks outside of the rendered range are drawn.
+#[derive(Clone, Default)]
+pub enum Padding {
+    /// White tiles.
+    #[default]
+    White,
+    /// No tile at all.
+    Omit,
+    /// Tiles with transparent fill and border.
+    Transparent,
+    /// Tiles drawn like the given element.
+    Element(Arc<dyn Element + Send + Sync>),
+}
+
+/// Rendering choices shared by all the days of a calendar.
+pub(crate) struct DayStyle {
+    /// Show the day of the month as glyph.
+    numbered: bool,
+    padding: Padding,
+    link_template: Option<String>,
+    title_template: Option<String>,
+    locale: Locale,
+    today: Option<NaiveDate>,
+    future_color: Option<Rgb>,
+}
+
+impl DayStyle {
+    pub(crate) fn new(options: &DayOptions, numbered: bool) -> Self {
+        Self {
+            numbered,
+            padding: options.padding.clone(),
+            link_template: options.link_template.clone(),
+            title_template: options.title_template.clone(),
+            locale: options.locale,
+            today: options.today,
+            future_color: options.future_color.clone(),
+        }
+    }
+
+    /// Color greying out the date if it is after today.
+    fn future(&self, date: NaiveDate) -> Option<Rgb> {
+        self.future_color
+            .clone()
+            .filter(|_| self.today.map(|today| date > today).unwrap_or(false))
+    }
+
+    /// Title and link of the day from the templates, if the element has no link of its own.
+    fn link(&self, date: NaiveDate, element: &impl Element) -> Option<(String, String)> {
+        let link = self.link_template.as_ref().filter(|_| element.get_link().is_none())?;
+        let fill = |template: &str| {
+            template
+                .replace("{yyyy-mm-dd}", &date.format("%Y-%m-%d").to_string())
+                .replace("{date}", &date.format_localized("%x", self.locale).to_string())
+                .replace("{weekday}", &date.format_localized("%A", self.locale).to_string())
+                .replace(
+                    "{value}",
+                    &element.value().map(|v| format!("{}", v)).unwrap_or_default(),
+                )
+        };
+        let title = self.title_template.as_deref().unwrap_or("{yyyy-mm-dd}");
+        Some((fill(title), fill(link)))
+    }
+}
+
+/// Value of each day drawn, in order, to compute the statistics without a second lookup.
+pub(crate) type DayValues = Rc<RefCell<Vec<(NaiveDate, f64)>>>;
+
+pub(crate) struct Weeks<E: Element> {
+    layout: DateLayout,
+    week: u32,
     data_source: Rc<dyn DateDataSource<E>>,
-    year: i32,
+    style: Rc<DayStyle>,
+    values: Option<DayValues>,
 }
-impl<E: Element> Year<E> {
-    fn new(year: i32, data_source: Rc<dyn DateDataSource<E>>) -> Self {
+impl<E: Element> Weeks<E> {
+    pub(crate) fn new(layout: DateLayout, data_source: Rc<dyn DateDataSource<E>>, style: Rc<DayStyle>) -> Self {
         Self {
-            date: Box::new(
-                NaiveDate::from_ymd_opt(year, 1, 1)
-                    .unwrap()
-                    .iter_weeks()
-                    .take_while(move |x| x.year() == year),
-            ),
+            layout,
+            week: 0,
             data_source,
-            year,
+            style,
+            values: None,
         }
     }
+
+    /// Record the value of each day into `values` while the weeks are drawn.
+    pub(crate) fn record_values(&mut self, values: DayValues) {
+        self.values = Some(values);
+    }
 }
 
-impl<E: Element> Iterator for Year<E> {
+impl<E: Element> Iterator for Weeks<E> {
     type Item = Week<E>;
     fn next(&mut self) -> Option<Self::Item> {
-        let ds = self.data_source.clone();
-        self.date.next().map(|w| Week::new(w, self.year, ds))
+        if self.week < self.layout.weeks() {
+            let mut week = Week::new(
+                self.layout.clone(),
+                self.week,
+                self.data_source.clone(),
+                self.style.clone(),
+            );
+            week.values = self.values.clone();
+            self.week += 1;
+            Some(week)
+        } else {
+            None
+        }
     }
 }
 
-struct Week<E: Element> {
-    date: Box<dyn Iterator<Item = NaiveDate>>,
+pub(crate) struct Week<E: Element> {
+    layout: DateLayout,
+    week: u32,
+    day: u32,
     data_source: Rc<dyn DateDataSource<E>>,
-    empty: u32,
+    style: Rc<DayStyle>,
+    values: Option<DayValues>,
 }
 impl<E: Element> Week<E> {
-    fn new(date: NaiveDate, year: i32, data_source: Rc<dyn DateDataSource<E>>) -> Self {
-        let weekday = if date.iso_week().year() != year {
-            let rem = 7 - date.weekday().number_from_sunday();
-            date.checked_add_days(chrono::Days::new(rem as u64)).unwrap()
-        } else {
-            date.checked_add_days(chrono::Days::new(7)).unwrap()
-        };
-        let empty = if date.iso_week().year() != year {
-            date.weekday().number_from_sunday()
-        } else {
-            0
-        };
+    fn new(layout: DateLayout, week: u32, data_source: Rc<dyn DateDataSource<E>>, style: Rc<DayStyle>) -> Self {
         Self {
-            date: Box::new(date.iter_days().take_while(move |d| d != &weekday)),
-            empty,
+            layout,
+            week,
+            day: 0,
             data_source,
+            style,
+            values: None,
         }
     }
 }
@@ -65,55 +440,220 @@ impl<E: Element> Week<E> {
 impl<E: Element> Iterator for Week<E> {
     type Item = WrapperElement<E>;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.empty > 0 {
-            self.empty -= 1;
-            Some(WrapperElement::None)
+        if self.day >= 7 {
+            return None;
+        }
+        let date = self.layout.date(self.week, self.day);
+        self.day += 1;
+        if let Some(date) = date.filter(|date| self.layout.contains(*date)) {
+            let element = self.data_source.get_element(date);
+            if let Some(values) = &self.values {
+                values.borrow_mut().push((date, element.value().unwrap_or(0.0)));
+            }
+            let link = self.style.link(date, &element);
+            let mut day = if self.style.numbered {
+                WrapperElement::Numbered(element, date.day())
+            } else {
+                WrapperElement::Real(element)
+            };
+            if let Some(grey) = self.style.future(date) {
+                day = WrapperElement::Dimmed(Box::new(day), grey);
+            }
+            match link {
+                Some(link) => Some(WrapperElement::Linked(Box::new(day), link)),
+                None => Some(day),
+            }
         } else {
-            let ds = self.data_source.clone();
-            self.date.next().map(|d| WrapperElement::Real(ds.get_element(d)))
+            Some(WrapperElement::Padding(self.style.padding.clone()))
         }
     }
 }
 
-enum WrapperElement<E> {
+pub(crate) enum WrapperElement<E> {
     Real(E),
-    None,
+    /// Day showing its day of the month as glyph.
+    Numbered(E, u32),
+    Padding(Padding),
+    /// Day with the given title and link in place of its own.
+    Linked(Box<WrapperElement<E>>, (String, String)),
+    /// Day with its colors mixed half and half with the grey.
+    Dimmed(Box<WrapperElement<E>>, Rgb),
+}
+
+/// Mix of the color with the grey, keeping the transparency of the color.
+fn dim(color: Rgb, grey: &Rgb) -> Rgb {
+    let mix = |c: f64, g: f64| (c + g) / 2.0;
+    Rgb::new(
+        mix(color.red(), grey.red()),
+        mix(color.green(), grey.green()),
+        mix(color.blue(), grey.blue()),
+        Some(color.alpha()),
+    )
 }
 impl<E: Element> Element for WrapperElement<E> {
     fn get_color(&self) -> Rgb {
         match self {
-            Self::Real(e) => e.get_color(),
-            Self::None => Rgb::new(255.0, 255.0, 255.0, Some(255.0)),
+            Self::Real(e) | Self::Numbered(e, _) => e.get_color(),
+            Self::Padding(Padding::Element(e)) => e.get_color(),
+            Self::Padding(Padding::Transparent) => Rgb::new(255.0, 255.0, 255.0, Some(0.0)),
+            Self::Padding(_) => Rgb::new(255.0, 255.0, 255.0, Some(255.0)),
+            Self::Linked(e, _) => e.get_color(),
+            Self::Dimmed(e, grey) => dim(e.get_color(), grey),
         }
     }
 
     fn get_border_color(&self) -> Rgb {
         match self {
-            Self::Real(e) => e.get_border_color(),
-            Self::None => Rgb::new(255.0, 255.0, 255.0, Some(255.0)),
+            Self::Real(e) | Self::Numbered(e, _) => e.get_border_color(),
+            Self::Padding(Padding::Element(e)) => e.get_border_color(),
+            Self::Padding(Padding::Transparent) => Rgb::new(255.0, 255.0, 255.0, Some(0.0)),
+            Self::Padding(_) => Rgb::new(255.0, 255.0, 255.0, Some(255.0)),
+            Self::Linked(e, _) => e.get_border_color(),
+            Self::Dimmed(e, grey) => dim(e.get_border_color(), grey),
         }
     }
 
     fn get_link(&self) -> Option<Box<dyn ElementLink>> {
         match self {
-            Self::Real(e) => e.get_link(),
-            Self::None => None,
+            Self::Real(e) | Self::Numbered(e, _) => e.get_link(),
+            Self::Padding(Padding::Element(e)) => e.get_link(),
+            Self::Padding(_) => None,
+            Self::Linked(_, link) => Some(Box::new(link.clone())),
+            Self::Dimmed(e, _) => e.get_link(),
+        }
+    }
+
+    fn glyph(&self) -> Option<String> {
+        match self {
+            Self::Real(e) => e.glyph(),
+            Self::Numbered(_, day) => Some(format!("{}", day)),
+            Self::Padding(Padding::Element(e)) => e.glyph(),
+            Self::Padding(_) => None,
+            Self::Linked(e, _) | Self::Dimmed(e, _) => e.glyph(),
         }
     }
+
+    fn is_visible(&self) -> bool {
+        match self {
+            Self::Real(e) | Self::Numbered(e, _) => e.is_visible(),
+            Self::Padding(Padding::Element(e)) => e.is_visible(),
+            Self::Padding(Padding::Omit) => false,
+            Self::Padding(_) => true,
+            Self::Linked(e, _) | Self::Dimmed(e, _) => e.is_visible(),
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        match self {
+            Self::Real(e) | Self::Numbered(e, _) => e.value(),
+            Self::Padding(_) => None,
+            Self::Linked(e, _) | Self::Dimmed(e, _) => e.value(),
+        }
+    }
+}
+
+/// Localized names used by the calendar labels.
+#[derive(Clone)]
+pub(crate) struct CalendarNames {
+    week_start: Weekday,
+    locale: Locale,
 }
 
-struct YearMetadata {}
-struct YearInfo {
+impl CalendarNames {
+    pub(crate) fn new(week_start: Weekday, locale: Locale) -> Self {
+        Self { week_start, locale }
+    }
+
+    pub(crate) fn month_name(&self, month: u32) -> String {
+        NaiveDate::from_ymd_opt(2000, month, 1)
+            .unwrap()
+            .format_localized("%b", self.locale)
+            .to_string()
+    }
+
+    pub(crate) fn month_full_name(&self, month: u32) -> String {
+        NaiveDate::from_ymd_opt(2000, month, 1)
+            .unwrap()
+            .format_localized("%B", self.locale)
+            .to_string()
+    }
+
+    /// Abbreviated name of the weekday `days` after the week start.
+    pub(crate) fn weekday_name(&self, days: u32) -> String {
+        // 2023-01-02 is a Monday
+        let monday = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        let date = monday + Days::new((self.week_start.num_days_from_monday() + days) as u64);
+        date.format_localized("%a", self.locale).to_string()
+    }
+
+    /// Capitalized initial of the weekday `days` after the week start.
+    pub(crate) fn weekday_initial(&self, days: u32) -> String {
+        let name = self.weekday_name(days);
+        name.chars().take(1).flat_map(|c| c.to_uppercase()).collect()
+    }
+}
+
+struct YearMetadata {
+    layout: DateLayout,
+    names: CalendarNames,
+    /// Whether the weeks are columns, with the month labels on top.
+    weeks_columns: bool,
+    week_labels: WeekLabels,
+    /// Labels of the series stacked along the days, drawn in place of the weekday initials.
+    series: Vec<String>,
+    /// Draw the weekday initials, only the first strip of a stack has them.
+    weekdays: bool,
+}
+
+impl YearMetadata {
+    fn month_labels(&self) -> Vec<YearInfo> {
+        let starts = self.layout.month_starts();
+        let mut infos = Vec::new();
+        for (i, (month, week)) in starts.iter().enumerate() {
+            let next = starts.get(i + 1).map(|(_, w)| *w).unwrap_or(self.layout.weeks());
+            infos.push(YearInfo::new(&self.names.month_name(*month), next - week));
+        }
+        infos
+    }
+
+    fn week_number_labels(&self) -> Vec<YearInfo> {
+        self.layout
+            .week_numbers()
+            .into_iter()
+            .map(|week| YearInfo::new(&format!("{}", week), 1))
+            .collect()
+    }
+}
+
+pub(crate) struct YearInfo {
     block_count: u32,
     label: String,
 }
 impl YearInfo {
-    fn new(label: &str, size: u32) -> Self {
+    pub(crate) fn new(label: &str, size: u32) -> Self {
         Self {
             block_count: size,
             label: label.to_owned(),
         }
     }
+
+    /// A label every `span` hours of the day, formatted by `label`.
+    pub(crate) fn hours(span: u32, label: impl Fn(u32) -> String) -> std::vec::IntoIter<YearInfo> {
+        (0..24)
+            .step_by(span as usize)
+            .map(|hour| YearInfo::new(&label(hour), span))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// Space of the month names, wider when they are on the side of the grid than on top of it.
+pub(crate) fn month_labels_size(on_top: bool) -> u32 {
+    if on_top {
+        20
+    } else {
+        35
+    }
 }
 
 impl Info for YearInfo {
@@ -128,39 +668,57 @@ impl Info for YearInfo {
 
 impl Metadata<std::vec::IntoIter<YearInfo>, YearInfo> for YearMetadata {
     fn left_size(&self) -> u32 {
-        20
+        match self.week_labels {
+            // at most two digits
+            WeekLabels::Weeks => 20,
+            WeekLabels::Months | WeekLabels::Both => month_labels_size(self.weeks_columns),
+        }
     }
 
     fn top_size(&self) -> u32 {
-        20
+        // the series labels are on the side of the days when the weeks are columns
+        let longest = self.series.iter().map(|s| s.chars().count() as u32).max();
+        match longest {
+            Some(chars) if self.weeks_columns => chars * SERIES_CHAR_WIDTH + 5,
+            _ => 20,
+        }
     }
 
     fn left(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        match self.week_labels {
+            WeekLabels::Months | WeekLabels::Both => Some(self.month_labels().into_iter()),
+            WeekLabels::Weeks => Some(self.week_number_labels().into_iter()),
+        }
+    }
+
+    fn top(&self) -> Option<std::vec::IntoIter<YearInfo>> {
+        if !self.series.is_empty() {
+            return Some(
+                self.series
+                    .iter()
+                    .map(|label| YearInfo::new(label, 7))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            );
+        }
+        if !self.weekdays {
+            return None;
+        }
         Some(
             vec![
-                YearInfo::new("Jan", 5),
-                YearInfo::new("Feb", 4),
-                YearInfo::new("Mar", 5),
-                YearInfo::new("Apr", 4),
-                YearInfo::new("May", 5),
-                YearInfo::new("Jun", 4),
-                YearInfo::new("Jul", 4),
-                YearInfo::new("Aug", 5),
-                YearInfo::new("Sep", 4),
-                YearInfo::new("Oct", 5),
-                YearInfo::new("Nov", 4),
-                YearInfo::new("Dec", 5),
+                YearInfo::new(&self.names.weekday_initial(0), 3),
+                YearInfo::new(&self.names.weekday_initial(3), 3),
+                YearInfo::new(&self.names.weekday_initial(6), 1),
             ]
             .into_iter(),
         )
     }
 
-    fn top(&self) -> Option<std::vec::IntoIter<YearInfo>> {
-        Some(vec![YearInfo::new("S", 3), YearInfo::new("T", 3), YearInfo::new("S", 1)].into_iter())
-    }
-
     fn right(&self) -> Option<std::vec::IntoIter<YearInfo>> {
-        None
+        match self.week_labels {
+            WeekLabels::Both => Some(self.week_number_labels().into_iter()),
+            WeekLabels::Months | WeekLabels::Weeks => None,
+        }
     }
 
     fn bottom(&self) -> Option<std::vec::IntoIter<YearInfo>> {
@@ -172,15 +730,326 @@ pub fn year_line<W, D, E>(
     year: i32,
     data_source: D,
     output: W,
+    config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    year_line_with_options(year, data_source, output, config, YearOptions::new().build())
+}
+
+pub fn year_line_with_options<W, D, E>(
+    year: i32,
+    data_source: D,
+    output: W,
+    config: Config,
+    options: YearOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    let layout = options.year_layout(year)?;
+    render_layout(layout, data_source, output, config, options)
+}
+
+/// Render all the days between `start` and `end` inclusive, like the last 365 days.
+pub fn date_range_line<W, D, E>(
+    start: NaiveDate,
+    end: NaiveDate,
+    data_source: D,
+    output: W,
+    config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    date_range_line_with_options(start, end, data_source, output, config, YearOptions::new().build())
+}
+
+pub fn date_range_line_with_options<W, D, E>(
+    start: NaiveDate,
+    end: NaiveDate,
+    data_source: D,
+    output: W,
+    config: Config,
+    options: YearOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    if start > end {
+        return Err(format!("the range start {} is after the range end {}", start, end).into());
+    }
+    let layout = DateLayout::new(start, end, options.week_start())
+        .ok_or_else(|| format!("the range from {} to {} is out of the supported dates", start, end))?;
+    render_layout(layout, data_source, output, config, options)
+}
+
+/// Overlays of the layout requested by the options.
+fn layout_overlays(layout: &DateLayout, options: &YearOptions) -> Vec<Overlay> {
+    let mut overlays = Vec::new();
+    for interval in &options.intervals {
+        let (from, to) = interval.bounds();
+        if from.max(to) >= &layout.start && from.min(to) <= &layout.end {
+            overlays.push(Overlay::Bar(interval.map(|d| layout.cell(*d))));
+        }
+    }
+    overlays.extend(month_outlines(layout, options));
+    overlays.extend(layout_annotations(layout, options));
+    overlays.extend(options.days.day_overlays(layout));
+    overlays
+}
+
+fn month_outlines(layout: &DateLayout, options: &YearOptions) -> Vec<Overlay> {
+    match &options.month_outline {
+        Some(color) => layout.month_outlines(color),
+        None => Vec::new(),
+    }
+}
+
+fn layout_annotations(layout: &DateLayout, options: &YearOptions) -> Vec<Overlay> {
+    let mut overlays = Vec::new();
+    for annotation in &options.annotations {
+        let (from, to) = annotation.bounds();
+        if from.max(to) >= &layout.start && from.min(to) <= &layout.end {
+            overlays.push(Overlay::Annotation(annotation.map(|d| layout.cell(*d))));
+        }
+    }
+    overlays
+}
+
+fn render_layout<W, D, E>(
+    layout: DateLayout,
+    data_source: D,
+    output: W,
+    mut config: Config,
+    options: YearOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    config.default_mode(Mode::ColumnRow);
+    let metadata = YearMetadata {
+        layout: layout.clone(),
+        names: CalendarNames::new(options.week_start(), options.days.locale),
+        weeks_columns: config.first_horizontal(),
+        week_labels: options.week_labels,
+        series: Vec::new(),
+        weekdays: true,
+    };
+    let overlays = layout_overlays(&layout, &options);
+    let data = Weeks::new(
+        layout,
+        Rc::new(data_source),
+        Rc::new(DayStyle::new(&options.days, false)),
+    );
+    let strip = Mutex::new(Some((config, metadata, data)));
+    base_doc(output, move |svg| {
+        let (config, metadata, data) = strip.lock().unwrap().take().unwrap();
+        write_year_strip(svg, config, metadata, data, &overlays, options.stats_footer)?;
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Write the days with their labels and overlays, then the statistics footer after the second
+/// axis, past the `right` labels and the annotations. Return the space taken along the second
+/// axis besides the days.
+fn write_year_strip<W: Write, E: Element>(
+    svg: &mut Writer<W>,
+    config: Config,
+    metadata: YearMetadata,
+    mut weeks: Weeks<E>,
+    overlays: &[Overlay],
+    stats_footer: bool,
+) -> std::result::Result<u32, quick_xml::Error> {
+    let top_size = metadata.top_size();
+    let left_size = metadata.left_size();
+    let after = right_labels_size(&config, &metadata) + annotations_size(&config, overlays);
+    let values = DayValues::default();
+    if stats_footer {
+        weeks.record_values(values.clone());
+    }
+    let (_, max_second) = write_metadata_tile(svg, config.clone(), metadata, weeks, overlays)?;
+    if !stats_footer {
+        return Ok(left_size + after);
+    }
+    let footer = DateStats::from_values(values.take()).summary();
+    let mut c = config;
+    c.set_metadata_first_offset(top_size);
+    c.set_metadata_second_offset(left_size);
+    c.set_metadata_after_second_offset(max_second);
+    c.set_metadata_second_offset(after);
+    write_text(svg, &c, 0, 0, &footer, None)?;
+    Ok(left_size + after + text_size(&c, &footer).ceil() as u32)
+}
+
+/// Render a year line for each year of the range, with the weekdays aligned.
+pub fn years_line<W, D, E>(
+    years: RangeInclusive<i32>,
+    data_source: D,
+    output: W,
+    config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: DateDataSource<E> + 'static,
+    E: Element,
+    W: Write,
+{
+    years_line_with_options(years, data_source, output, config, YearOptions::new().build())
+}
+
+pub fn years_line_with_options<W, D, E>(
+    years: RangeInclusive<i32>,
+    data_source: D,
+    output: W,
     mut config: Config,
+    options: YearOptions,
 ) -> std::result::Result<(), Box<dyn std::error::Error>>
 where
     D: DateDataSource<E> + 'static,
     E: Element,
     W: Write,
 {
-    config.set_mode(Mode::ColumnRow);
-    let metadata = YearMetadata {};
-    metadata_tile(config, metadata, Year::new(year, Rc::new(data_source)), output)?;
+    if years.is_empty() {
+        return Err(format!("empty range of years {:?}", years).into());
+    }
+    config.default_mode(Mode::ColumnRow);
+    let names = CalendarNames::new(options.week_start(), options.days.locale);
+    let weeks_columns = config.first_horizontal();
+    let layouts = years
+        .map(|year| Ok((year, options.year_layout(year)?)))
+        .collect::<std::result::Result<Vec<_>, String>>()?;
+    let (step_x, step_y) = config.step();
+    let data_source: Rc<dyn DateDataSource<E>> = Rc::new(data_source);
+    let style = Rc::new(DayStyle::new(&options.days, false));
+    base_doc(output, move |svg| {
+        let mut c = config.clone();
+        for (index, (year, layout)) in layouts.iter().enumerate() {
+            let metadata = YearMetadata {
+                layout: layout.clone(),
+                names: names.clone(),
+                weeks_columns,
+                week_labels: options.week_labels,
+                series: Vec::new(),
+                // the strips stacked along the weekdays share the header of the first one
+                weekdays: index == 0 || !weeks_columns,
+            };
+            let (x, y) = c.offset();
+            write_free_text(svg, x as f64, y as f64, "hanging", &format!("{}", year))?;
+            let mut strip = c.clone();
+            strip.translate(0, TITLE_SIZE);
+            let overlays = layout_overlays(layout, &options);
+            let weeks = Weeks::new(layout.clone(), data_source.clone(), style.clone());
+            let strip_size = write_year_strip(svg, strip, metadata, weeks, &overlays, options.stats_footer)?;
+            if weeks_columns {
+                c.translate(0, TITLE_SIZE + strip_size + 7 * step_y + BLOCK_SPACING);
+            } else {
+                c.translate(strip_size + 7 * step_x + BLOCK_SPACING, 0);
+            }
+        }
+        Ok(())
+    })?;
+    Ok(())
+}
+
+/// Render a year line for each series of the data source, sharing the weeks.
+pub fn multi_year_line<W, D, E>(
+    year: i32,
+    data_source: D,
+    output: W,
+    config: Config,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: MultiDateDataSource<E> + 'static,
+    E: Element + 'static,
+    W: Write,
+{
+    multi_year_line_with_options(year, data_source, output, config, YearOptions::new().build())
+}
+
+pub fn multi_year_line_with_options<W, D, E>(
+    year: i32,
+    data_source: D,
+    output: W,
+    mut config: Config,
+    options: YearOptions,
+) -> std::result::Result<(), Box<dyn std::error::Error>>
+where
+    D: MultiDateDataSource<E> + 'static,
+    E: Element + 'static,
+    W: Write,
+{
+    let labels = data_source.labels();
+    if labels.is_empty() {
+        return Err("no series to render".into());
+    }
+    if !options.intervals.is_empty() {
+        return Err("the intervals are not supported with several series".into());
+    }
+    if options.stats_footer {
+        return Err("the statistics footer is not supported with several series".into());
+    }
+    config.default_mode(Mode::ColumnRow);
+    if config.second_groups().is_empty() {
+        config.set_second_groups((1..labels.len() as u32).map(|series| series * 7).collect());
+    }
+    let layout = options.year_layout(year)?;
+    let offsets = (0..labels.len() as u32).map(|series| series * 7).collect::<Vec<_>>();
+    let mut overlays = Vec::new();
+    for offset in &offsets {
+        overlays.extend(
+            month_outlines(&layout, &options)
+                .into_iter()
+                .map(|o| o.shift_second(*offset)),
+        );
+    }
+    overlays.extend(layout_annotations(&layout, &options));
+    for offset in &offsets {
+        overlays.extend(
+            options
+                .days
+                .day_overlays(&layout)
+                .into_iter()
+                .map(|o| o.shift_second(*offset)),
+        );
+    }
+    let source: Rc<dyn MultiDateDataSource<E>> = Rc::new(data_source);
+    let series = (0..labels.len())
+        .map(|index| {
+            Rc::new(Series {
+                source: source.clone(),
+                index,
+            }) as Rc<dyn DateDataSource<E>>
+        })
+        .collect::<Vec<_>>();
+    let metadata = YearMetadata {
+        layout: layout.clone(),
+        names: CalendarNames::new(options.week_start(), options.days.locale),
+        weeks_columns: config.first_horizontal(),
+        week_labels: options.week_labels,
+        series: labels,
+        weekdays: true,
+    };
+    let style = Rc::new(DayStyle::new(&options.days, false));
+    let weeks = layout.weeks();
+    let data = (0..weeks).map(move |week| {
+        series
+            .iter()
+            .flat_map(|source| Week::new(layout.clone(), week, source.clone(), style.clone()))
+            .collect::<Vec<_>>()
+            .into_iter()
+    });
+    metadata_tile_with_overlays(config, metadata, data, overlays, output)?;
     Ok(())
 }
@@ -1,4 +1,7 @@
-use tileline::{metadata_tile, tile, Config, Element, ElementLink, Info, Metadata, Mode, Rgb};
+use tileline::{
+    group_starts, metadata_tile, metadata_tile_with_overlays, tile, tile_with_overlays, Annotation, AnnotationStyle,
+    Bar, Config, Element, ElementLink, Info, Metadata, Mode, Outline, Overlay, Rgb,
+};
 
 #[derive(Clone)]
 struct Value {
@@ -150,45 +153,1132 @@ fn test_metadata() {
 }
 
 #[test]
-#[cfg(feature = "year_line")]
-fn test_year_line() {
-    use chrono::{Datelike, NaiveDate};
-    struct YearDatasource {}
-    struct DateElement {
-        color: Rgb,
-        date: NaiveDate,
-    }
-    impl Element for DateElement {
-        fn get_color(&self) -> Rgb {
-            self.color.clone()
+fn test_metadata_groups() {
+    let mut val = Vec::new();
+    for i in 0..5 {
+        let mut column = Vec::new();
+        for z in 0..30 {
+            column.push(Value::new(i * 10 + z * 10));
+        }
+        val.push(column.into_iter());
+    }
+
+    let meta = Meta::default();
+    let config = Config::new()
+        .first_groups(group_starts(meta.left().unwrap()))
+        .second_groups(group_starts(meta.top().unwrap()))
+        .build();
+    let mut out = Vec::new();
+    metadata_tile(config, meta, val.into_iter(), &mut out).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/simple_groups.svg").unwrap().to_vec());
+}
+
+#[test]
+fn test_outline() {
+    let mut val = Vec::new();
+    for i in 0..5 {
+        let mut column = Vec::new();
+        for z in 0..10 {
+            column.push(Value::new(i * 10 + z * 10));
+        }
+        val.push(column.into_iter());
+    }
+
+    let red = Rgb::new(255.0, 0.0, 0.0, None);
+    let overlays = vec![
+        Overlay::Outline(Outline::range((1, 1), (2, 3), red.clone()).label("range")),
+        Overlay::Outline(Outline::new(vec![(0, 6), (0, 7), (1, 7), (2, 7), (2, 8)], red).width(1)),
+    ];
+    let config = Config::new().offset_x(10).offset_y(20).build();
+    let mut out = Vec::new();
+    tile_with_overlays(config, val.into_iter(), overlays, &mut out).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/simple_outline.svg").unwrap().to_vec());
+}
+
+#[test]
+fn test_outline_groups() {
+    let val = (0..4).map(|i| {
+        (0..3)
+            .map(move |z| Value::new(i * 10 + z * 10))
+            .collect::<Vec<_>>()
+            .into_iter()
+    });
+    let red = Rgb::new(255.0, 0.0, 0.0, None);
+    let overlays = vec![
+        Overlay::Outline(Outline::range((0, 0), (1, 1), red.clone())),
+        Overlay::Outline(Outline::new(vec![(1, 2), (2, 2), (2, 1)], red)),
+    ];
+    let config = Config::new().first_groups(vec![2]).second_groups(vec![2]).build();
+    let mut out = Vec::new();
+    tile_with_overlays(config, val, overlays, &mut out).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    // the outline ending before a group closes on its last cells, not across the gap
+    assert!(out.contains(r#"<path d="M-2.5 -2.5 L-2.5 37.5 L37.5 37.5 L37.5 -2.5 Z""#));
+    // the outline spanning the gaps keeps them inside, with the inner corner on the last cell
+    assert!(out.contains(r#"<path d="M47.5 17.5 L47.5 47.5 L17.5 47.5 L17.5 67.5 L67.5 67.5 L67.5 17.5 Z""#));
+}
+
+#[test]
+fn test_annotation() {
+    let mut val = Vec::new();
+    for i in 0..10 {
+        let mut column = Vec::new();
+        for z in 0..5 {
+            column.push(Value::new(i * 10 + z * 10));
         }
+        val.push(column.into_iter());
+    }
+
+    let overlays = vec![
+        Overlay::Annotation(Annotation::new((1, 0), (4, 4), "Incident")),
+        Overlay::Annotation(Annotation::new((3, 0), (6, 0), "Release").color(Rgb::new(0.0, 0.0, 255.0, None))),
+        Overlay::Annotation(Annotation::new((8, 2), (8, 2), "Deploy").style(AnnotationStyle::Marker)),
+    ];
+    let config = Config::new().build();
+    let mut out = Vec::new();
+    tile_with_overlays(config, val.into_iter(), overlays, &mut out).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/simple_annotation.svg").unwrap().to_vec());
+}
 
-        fn get_border_color(&self) -> Rgb {
-            self.color.clone()
+#[test]
+fn test_metadata_annotation() {
+    let mut val = Vec::new();
+    for i in 0..5 {
+        let mut column = Vec::new();
+        for z in 0..30 {
+            column.push(Value::new(i * 10 + z * 10));
         }
+        val.push(column.into_iter());
+    }
+
+    let overlays = vec![Overlay::Annotation(Annotation::new((1, 0), (3, 0), "Incident"))];
+    let config = Config::new().build();
+    let mut out = Vec::new();
+    metadata_tile_with_overlays(config, Meta::default(), val.into_iter(), overlays, &mut out).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/metadata_annot.svg").unwrap().to_vec());
+}
 
-        fn get_link(&self) -> Option<Box<dyn ElementLink>> {
-            Some(Box::new(ElementLinkImpl(format!("{}", self.date), "bbb".to_owned())))
+#[test]
+fn test_bar() {
+    let mut val = Vec::new();
+    for i in 0..6 {
+        let mut column = Vec::new();
+        for z in 0..5 {
+            column.push(Value::new(i * 10 + z * 10));
         }
+        val.push(column.into_iter());
+    }
+
+    let red = Rgb::new(255.0, 0.0, 0.0, None);
+    let overlays = vec![
+        Overlay::Bar(Bar::new((1, 3), (3, 1), (red.clone(), red, None))),
+        Overlay::Bar(Bar::from((
+            (5, 2),
+            (5, 0),
+            (
+                Rgb::new(0.0, 0.0, 255.0, Some(0.5)),
+                Rgb::new(0.0, 0.0, 255.0, None),
+                Some(("On call".to_owned(), "https://oncall".to_owned())),
+            ),
+        ))),
+    ];
+    let config = Config::new().build();
+    let mut out = Vec::new();
+    tile_with_overlays(config, val.into_iter(), overlays, &mut out).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/simple_bar.svg").unwrap().to_vec());
+}
+
+struct GlyphValue(u32);
+impl Element for GlyphValue {
+    fn get_color(&self) -> Rgb {
+        let level = 255.0 - (self.0 % 256) as f64;
+        Rgb::new(level, level, 255.0, None)
+    }
+    fn get_border_color(&self) -> Rgb {
+        Rgb::new(0.0, 0.0, 50.0, None)
+    }
+    fn get_link(&self) -> Option<Box<dyn ElementLink>> {
+        None
+    }
+    fn glyph(&self) -> Option<String> {
+        Some(format!("{}", self.0))
+    }
+}
+
+#[test]
+fn test_glyph() {
+    let val = vec![
+        vec![GlyphValue(1), GlyphValue(12), GlyphValue(250)].into_iter(),
+        vec![GlyphValue(7), GlyphValue(99), GlyphValue(1234)].into_iter(),
+    ];
+    let config = Config::new().size_x(20).size_y(20).build();
+    let mut out = Vec::new();
+    tile(config, val.into_iter(), &mut out).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/simple_glyph.svg").unwrap().to_vec());
+}
+
+#[test]
+fn test_rect_size() {
+    let val = vec![vec![Value::new(0)].into_iter()];
+    let config = Config::new().size_x(20).size_y(10).build();
+    let mut out = Vec::new();
+    tile(config, val.into_iter(), &mut out).unwrap();
+    let svg = String::from_utf8(out).unwrap();
+    assert!(svg.contains("width=\"20\" height=\"10\""));
+}
+
+#[cfg(feature = "year_line")]
+struct YearDatasource {}
+#[cfg(feature = "year_line")]
+struct DateElement {
+    color: Rgb,
+    date: chrono::NaiveDate,
+}
+#[cfg(feature = "year_line")]
+impl Element for DateElement {
+    fn get_color(&self) -> Rgb {
+        self.color.clone()
+    }
+
+    fn get_border_color(&self) -> Rgb {
+        self.color.clone()
+    }
+
+    fn get_link(&self) -> Option<Box<dyn ElementLink>> {
+        Some(Box::new(ElementLinkImpl(format!("{}", self.date), "bbb".to_owned())))
     }
-    impl tileline::DateDataSource<DateElement> for YearDatasource {
-        fn get_element(&self, data: chrono::NaiveDate) -> DateElement {
-            if data.day() % 2 == 0 {
-                DateElement {
-                    color: Rgb::new(0.0, 0.0, 100.0, None),
-                    date: data.clone(),
-                }
-            } else {
-                DateElement {
-                    color: Rgb::new(0.0, 100.0, 0.0, None),
-                    date: data.clone(),
-                }
+}
+#[cfg(feature = "year_line")]
+impl tileline::DateDataSource<DateElement> for YearDatasource {
+    fn get_element(&self, data: chrono::NaiveDate) -> DateElement {
+        use chrono::Datelike;
+        if data.day() % 2 == 0 {
+            DateElement {
+                color: Rgb::new(0.0, 0.0, 100.0, None),
+                date: data.clone(),
+            }
+        } else {
+            DateElement {
+                color: Rgb::new(0.0, 100.0, 0.0, None),
+                date: data.clone(),
             }
         }
     }
+}
 
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line() {
     let config = Config::new().build();
     let mut out = Vec::new();
     tileline::year_line(2023, YearDatasource {}, &mut out, config).unwrap();
     assert_eq!(out, std::fs::read("./fixtures/year_line.svg").unwrap().to_vec());
 }
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_every_day_once() {
+    for (year, days) in [(2023, 365), (2024, 366)] {
+        let mut out = Vec::new();
+        tileline::year_line(year, YearDatasource {}, &mut out, Config::new().build()).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        let dates = svg.split("xlink:href=\"").skip(1).map(|s| &s[..10]).collect::<Vec<_>>();
+        assert_eq!(dates.len(), days);
+        assert!(dates.iter().all(|d| d.starts_with(&format!("{}-", year))));
+        assert_eq!(dates.iter().collect::<std::collections::BTreeSet<_>>().len(), days);
+    }
+}
+
+struct LinkedMeta {}
+struct LinkedInfo(u32, &'static str, Option<&'static str>);
+impl Info for LinkedInfo {
+    fn block_count(&self) -> u32 {
+        self.0
+    }
+
+    fn label(&self) -> &str {
+        self.1
+    }
+
+    fn link(&self) -> Option<String> {
+        self.2.map(|l| format!("https://tglman.com/{}", l))
+    }
+
+    fn tooltip(&self) -> Option<String> {
+        self.2.map(|l| format!("Report {}", l))
+    }
+}
+
+impl Metadata<std::vec::IntoIter<LinkedInfo>, LinkedInfo> for LinkedMeta {
+    fn left_size(&self) -> u32 {
+        30
+    }
+
+    fn top_size(&self) -> u32 {
+        40
+    }
+
+    fn left(&self) -> Option<std::vec::IntoIter<LinkedInfo>> {
+        Some(vec![LinkedInfo(1, "Jan", Some("jan")), LinkedInfo(1, "Feb", None)].into_iter())
+    }
+
+    fn top(&self) -> Option<std::vec::IntoIter<LinkedInfo>> {
+        Some(
+            vec![
+                LinkedInfo(2, "first\nweek", None),
+                LinkedInfo(2, "second\nweek", Some("w2")),
+            ]
+            .into_iter(),
+        )
+    }
+
+    fn right(&self) -> Option<std::vec::IntoIter<LinkedInfo>> {
+        None
+    }
+
+    fn bottom(&self) -> Option<std::vec::IntoIter<LinkedInfo>> {
+        None
+    }
+}
+
+#[test]
+fn test_metadata_link() {
+    let mut val = Vec::new();
+    for i in 0..2 {
+        let mut column = Vec::new();
+        for z in 0..4 {
+            column.push(Value::new(i * 10 + z * 10));
+        }
+        val.push(column.into_iter());
+    }
+
+    let config = Config::new().build();
+    let mut out = Vec::new();
+    metadata_tile(config, LinkedMeta {}, val.into_iter(), &mut out).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/metadata_link.svg").unwrap().to_vec());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_month_outline() {
+    let config = Config::new().build();
+    let options = tileline::YearOptions::new()
+        .month_outline(Rgb::new(0.0, 0.0, 0.0, None))
+        .build();
+    let mut out = Vec::new();
+    tileline::year_line_with_options(2023, YearDatasource {}, &mut out, config, options).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/year_line_outline.svg").unwrap().to_vec());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_annotation() {
+    use chrono::NaiveDate;
+    let config = Config::new().build();
+    let options = tileline::YearOptions::new()
+        .annotations(vec![
+            Annotation::new(
+                NaiveDate::from_ymd_opt(2023, 3, 10).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 4, 20).unwrap(),
+                "Migration",
+            ),
+            Annotation::new(
+                NaiveDate::from_ymd_opt(2023, 4, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 4, 1).unwrap(),
+                "Release",
+            )
+            .style(AnnotationStyle::Marker),
+        ])
+        .build();
+    let mut out = Vec::new();
+    tileline::year_line_with_options(2023, YearDatasource {}, &mut out, config, options).unwrap();
+    assert_eq!(
+        out,
+        std::fs::read("./fixtures/year_line_annotation.svg").unwrap().to_vec()
+    );
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_week_start_locale() {
+    let config = Config::new().build();
+    let days = tileline::DayOptions::new()
+        .week_start(tileline::Weekday::Mon)
+        .locale(tileline::Locale::it_IT)
+        .build();
+    let options = tileline::YearOptions::new().days(days).build();
+    let mut out = Vec::new();
+    tileline::year_line_with_options(2023, YearDatasource {}, &mut out, config, options).unwrap();
+    assert_eq!(
+        out,
+        std::fs::read("./fixtures/year_line_monday_it.svg").unwrap().to_vec()
+    );
+}
+
+#[cfg(feature = "year_line")]
+fn svg_label(svg: &str, label: &str) -> Option<(u32, u32)> {
+    let end = svg.find(&format!(r#" dominant-baseline="hanging">{}</text>"#, label))?;
+    let start = svg[..end].rfind("<text ")?;
+    let attributes = svg[start..end].split('"').collect::<Vec<_>>();
+    Some((attributes[1].parse().ok()?, attributes[3].parse().ok()?))
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_weekday_labels() {
+    // the initials of the first, fourth and last day of the week, one row of 20px per day
+    for (week_start, labels) in [
+        (tileline::Weekday::Sun, ["S", "W", "S"]),
+        (tileline::Weekday::Mon, ["M", "T", "S"]),
+    ] {
+        let days = tileline::DayOptions::new().week_start(week_start).build();
+        let options = tileline::YearOptions::new().days(days).build();
+        let mut out = Vec::new();
+        tileline::year_line_with_options(2023, YearDatasource {}, &mut out, Config::new().build(), options).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        let weekdays = svg
+            .split(r#"<text x="0" "#)
+            .skip(1)
+            .map(|text| text.split(['>', '<']).nth(1).unwrap());
+        assert_eq!(weekdays.collect::<Vec<_>>(), labels);
+        assert_eq!(svg_label(&svg, labels[0]), Some((0, 20)));
+        assert_eq!(svg_label(&svg, labels[1]), Some((0, 80)));
+    }
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_month_labels() {
+    let config = Config::new().build();
+    let mut out = Vec::new();
+    tileline::year_line(2024, YearDatasource {}, &mut out, config).unwrap();
+    let svg = String::from_utf8(out).unwrap();
+    assert!(svg.contains(r#"<text x="20" y="0" dominant-baseline="hanging">Jan</text>"#));
+    assert!(svg.contains(r#"<text x="180" y="0" dominant-baseline="hanging">Mar</text>"#));
+    assert!(svg.contains(r#"<text x="720" y="0" dominant-baseline="hanging">Sep</text>"#));
+
+    // each label sits on the week containing the 1st of its month, one column of 20px per week
+    use chrono::{Datelike, NaiveDate};
+    for year in [2021, 2023, 2024] {
+        let mut out = Vec::new();
+        tileline::year_line(year, YearDatasource {}, &mut out, Config::new().build()).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        let jan_first = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let padding = jan_first.weekday().num_days_from_sunday();
+        for month in 1..=12 {
+            let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+            let week = (first.ordinal0() + padding) / 7;
+            let name = first.format("%b").to_string();
+            assert_eq!(svg_label(&svg, &name), Some((20 + week * 20, 0)), "{} {}", name, year);
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_out_of_range() {
+    let mut out = Vec::new();
+    assert!(tileline::year_line(262143, YearDatasource {}, &mut out, Config::new().build()).is_err());
+    let options = tileline::YearOptions::new().iso_weeks(true).build();
+    let mut out = Vec::new();
+    assert!(
+        tileline::year_line_with_options(-262144, YearDatasource {}, &mut out, Config::new().build(), options).is_err()
+    );
+    let mut out = Vec::new();
+    assert!(tileline::year_line(262142, YearDatasource {}, &mut out, Config::new().build()).is_ok());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_date_range_line_out_of_range() {
+    use chrono::{Days, NaiveDate};
+    let render = |start: NaiveDate, end: NaiveDate| {
+        let mut out = Vec::new();
+        tileline::date_range_line(start, end, YearDatasource {}, &mut out, Config::new().build())
+    };
+    assert!(render(NaiveDate::MIN, NaiveDate::MIN + Days::new(30)).is_err());
+    assert!(render(NaiveDate::MAX - Days::new(30), NaiveDate::MAX).is_err());
+    let mut out = Vec::new();
+    assert!(tileline::years_line(262142..=262144, YearDatasource {}, &mut out, Config::new().build()).is_err());
+    let mut out = Vec::new();
+    assert!(tileline::month_grid(262143, 12, YearDatasource {}, &mut out, Config::new().build()).is_err());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_date_range_line() {
+    use chrono::NaiveDate;
+    let config = Config::new().build();
+    let end = NaiveDate::from_ymd_opt(2024, 10, 19).unwrap();
+    let start = end - chrono::Days::new(364);
+    let mut out = Vec::new();
+    tileline::date_range_line(start, end, YearDatasource {}, &mut out, config).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/date_range_line.svg").unwrap().to_vec());
+
+    let mut out = Vec::new();
+    assert!(tileline::date_range_line(end, start, YearDatasource {}, &mut out, Config::new().build()).is_err());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_month_grid() {
+    let config = Config::new().build();
+    let mut out = Vec::new();
+    tileline::month_grid(2023, 7, YearDatasource {}, &mut out, config).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/month_grid.svg").unwrap().to_vec());
+
+    let mut out = Vec::new();
+    assert!(tileline::month_grid(2023, 13, YearDatasource {}, &mut out, Config::new().build()).is_err());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_wall_calendar() {
+    let config = Config::new().build();
+    let options = tileline::WallCalendarOptions::new().columns(4).build();
+    let mut out = Vec::new();
+    tileline::wall_calendar_with_options(2023, YearDatasource {}, &mut out, config, options).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/wall_calendar.svg").unwrap().to_vec());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_years_line() {
+    let config = Config::new().build();
+    let mut out = Vec::new();
+    tileline::years_line(2022..=2024, YearDatasource {}, &mut out, config).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/years_line.svg").unwrap().to_vec());
+    // one weekday header for the whole stack
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches(">W</text>").count(), 1);
+
+    // side by side each year keeps its own header
+    let config = Config::new().mode(Mode::RowColumn).build();
+    let mut out = Vec::new();
+    tileline::years_line(2022..=2024, YearDatasource {}, &mut out, config).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches(">W</text>").count(), 3);
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_weeks_rows() {
+    let config = Config::new().mode(Mode::RowColumn).build();
+    let mut out = Vec::new();
+    tileline::year_line(2023, YearDatasource {}, &mut out, config).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/year_line_rows.svg").unwrap().to_vec());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_options_send_sync() {
+    fn send_sync<T: Send + Sync>() {}
+    send_sync::<tileline::YearOptions>();
+    send_sync::<tileline::DayOptions>();
+    send_sync::<tileline::WallCalendarOptions>();
+    send_sync::<tileline::Padding>();
+    send_sync::<Overlay>();
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_padding() {
+    use std::sync::Arc;
+    use tileline::{DayOptions, Padding, YearOptions};
+    let render = |padding: Padding| {
+        let options = YearOptions::new()
+            .days(DayOptions::new().padding(padding).build())
+            .build();
+        let mut out = Vec::new();
+        tileline::year_line_with_options(2024, YearDatasource {}, &mut out, Config::new().build(), options).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+    // 2024 starts on Monday and ends on Tuesday, 1 and 4 days of padding
+    assert_eq!(render(Padding::White).matches("<rect").count(), 371);
+    assert_eq!(render(Padding::Omit).matches("<rect").count(), 366);
+    let transparent = render(Padding::Transparent);
+    assert_eq!(transparent.matches("fill-opacity:0;stroke-opacity:0").count(), 5);
+    let grey = Rgb::new(200.0, 200.0, 200.0, None);
+    let element = render(Padding::Element(Arc::new((grey.clone(), grey, None))));
+    assert_eq!(element.matches("fill:#c8c8c8").count(), 5);
+}
+
+#[test]
+fn test_color_scale() {
+    use tileline::{ColorScale, Scale};
+    let scale = ColorScale::greens(8.0);
+    assert_eq!(scale.color(0.0).to_hex_string(), "#ebedf0");
+    assert_eq!(scale.color(1.0).to_hex_string(), "#9be9a8");
+    assert_eq!(scale.color(4.0).to_hex_string(), "#40c463");
+    assert_eq!(scale.color(100.0).to_hex_string(), "#216e39");
+    assert_eq!(scale.element(3.0).value(), 3.0);
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_date_sources() {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use std::collections::HashMap;
+    use tileline::{ColorScale, DateDataSource, EventCounts};
+
+    let day = |d| NaiveDate::from_ymd_opt(2023, 5, d).unwrap();
+    let red = Rgb::new(255.0, 0.0, 0.0, None);
+    let white = Rgb::new(255.0, 255.0, 255.0, None);
+
+    let closure = |date: NaiveDate| {
+        let color = if date == day(1) { red.clone() } else { white.clone() };
+        (color.clone(), color, None)
+    };
+    assert_eq!(closure.get_element(day(1)).get_color().to_hex_string(), "#ff0000");
+
+    let mut map = HashMap::new();
+    map.insert(day(2), (red.clone(), red.clone(), None));
+    let source = (map, (white.clone(), white.clone(), None));
+    assert_eq!(source.get_element(day(2)).get_color().to_hex_string(), "#ff0000");
+    assert_eq!(source.get_element(day(3)).get_color().to_hex_string(), "#ffffff");
+
+    let events = vec![
+        NaiveDateTime::parse_from_str("2023-05-04 10:00:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+        NaiveDateTime::parse_from_str("2023-05-04 23:59:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+        NaiveDateTime::parse_from_str("2023-05-05 00:01:00", "%Y-%m-%d %H:%M:%S").unwrap(),
+    ];
+    let counts = EventCounts::new(events, ColorScale::greens(2.0));
+    assert_eq!(counts.count(day(4)), 2);
+    assert_eq!(counts.max_count(), 2);
+    assert_eq!(counts.get_element(day(4)).get_color().to_hex_string(), "#216e39");
+    assert_eq!(counts.get_element(day(6)).get_color().to_hex_string(), "#ebedf0");
+
+    let mut out = Vec::new();
+    let source = EventCounts::new(vec![day(4), day(4), day(9)], ColorScale::greens(2.0));
+    tileline::year_line(2023, source, &mut out, Config::new().build()).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap().matches("fill:#216e39").count(), 1);
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_aggregate_timezone() {
+    use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+    use tileline::{ColorScale, DailyValues, DateDataSource, EventCounts, Reducer};
+
+    let day = |d| NaiveDate::from_ymd_opt(2023, 5, d).unwrap();
+    let time = |s: &str| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc);
+    let events = vec![
+        (time("2023-05-04T23:30:00Z"), 3.0),
+        (time("2023-05-05T01:00:00Z"), 5.0),
+        (time("2023-05-05T12:00:00Z"), 1.0),
+    ];
+
+    let utc = DailyValues::aggregate(events.clone(), &Utc, Reducer::Sum, ColorScale::greens(6.0));
+    assert_eq!(utc.value(day(4)), Some(3.0));
+    assert_eq!(utc.value(day(5)), Some(6.0));
+    assert_eq!(utc.max_value(), Some(6.0));
+
+    let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+    let east = DailyValues::aggregate(events.clone(), &tokyo, Reducer::Max, ColorScale::greens(5.0));
+    assert_eq!(east.value(day(4)), None);
+    assert_eq!(east.value(day(5)), Some(5.0));
+    assert_eq!(east.get_element(day(5)).get_color().to_hex_string(), "#216e39");
+    assert_eq!(east.get_element(day(4)).get_color().to_hex_string(), "#ebedf0");
+
+    let new_york = FixedOffset::west_opt(4 * 3600).unwrap();
+    let west = DailyValues::aggregate(events.clone(), &new_york, Reducer::Min, ColorScale::greens(5.0));
+    assert_eq!(west.value(day(4)), Some(3.0));
+    assert_eq!(west.value(day(5)), Some(1.0));
+
+    let counts = EventCounts::new(
+        events.into_iter().map(|(t, _)| t.with_timezone(&new_york)),
+        ColorScale::greens(2.0),
+    );
+    assert_eq!(counts.count(day(4)), 2);
+    assert_eq!(counts.count(day(5)), 1);
+}
+
+#[test]
+#[cfg(feature = "chrono-tz")]
+fn test_aggregate_named_timezone() {
+    use chrono::{DateTime, NaiveDate, Utc};
+    use tileline::{aggregate_days, Reducer, Tz};
+
+    let time = DateTime::parse_from_rfc3339("2023-01-01T02:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let days = aggregate_days(vec![(time, 1.0)], &Tz::America__Los_Angeles, Reducer::Count);
+    assert_eq!(days.get(&NaiveDate::from_ymd_opt(2022, 12, 31).unwrap()), Some(&1.0));
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_punch_card() {
+    use chrono::{NaiveDate, Weekday};
+    use tileline::{ColorScale, PunchCard, PunchCardOptions};
+
+    // 2023-05-01 is a Monday
+    let at = |d, h| {
+        NaiveDate::from_ymd_opt(2023, 5, d)
+            .unwrap()
+            .and_hms_opt(h, 30, 0)
+            .unwrap()
+    };
+    let events = vec![at(1, 9), at(1, 9), at(8, 9), at(1, 14), at(3, 23), at(7, 0)];
+    let card = PunchCard::new(events, ColorScale::greens(3.0));
+    assert_eq!(card.count(Weekday::Mon, 9), 3);
+    assert_eq!(card.count(Weekday::Sun, 0), 1);
+    assert_eq!(card.max_count(), 3);
+
+    let mut out = Vec::new();
+    tileline::punch_card(card, &mut out, Config::new().build()).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/punch_card.svg").unwrap().to_vec());
+
+    let card = PunchCard::new(vec![at(1, 9)], ColorScale::greens(3.0));
+    let options = PunchCardOptions::new()
+        .week_start(Weekday::Mon)
+        .locale(tileline::Locale::it_IT)
+        .build();
+    let mut out = Vec::new();
+    tileline::punch_card_with_options(card, &mut out, Config::new().build(), options).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("<text x=\"0\" y=\"20\" dominant-baseline=\"hanging\">lun</text>"));
+
+    // the mode of the caller is kept, the weekdays become columns
+    let card = PunchCard::new(vec![at(1, 9)], ColorScale::greens(3.0));
+    let config = Config::new().mode(Mode::ColumnRow).build();
+    let mut out = Vec::new();
+    tileline::punch_card(card, &mut out, config).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("<text x=\"40\" y=\"0\" dominant-baseline=\"hanging\">Mon</text>"));
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_punch_card_sizes() {
+    use chrono::NaiveDate;
+    use tileline::{PunchCard, SizeScale};
+
+    let at = |h| {
+        NaiveDate::from_ymd_opt(2023, 5, 1)
+            .unwrap()
+            .and_hms_opt(h, 0, 0)
+            .unwrap()
+    };
+    let events = vec![at(9), at(9), at(9), at(9), at(10)];
+    let card = PunchCard::new(events, SizeScale::new(Rgb::new(0.0, 0.0, 255.0, None), 4.0));
+    let mut out = Vec::new();
+    tileline::punch_card(card, &mut out, Config::new().build()).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("<rect").count(), 2);
+    assert!(out.contains("width=\"15\" height=\"15\""));
+    assert!(out.contains("width=\"7.5\" height=\"7.5\""));
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_hourly_year_line() {
+    use chrono::{Datelike, NaiveDateTime, Timelike};
+
+    let red = Rgb::new(255.0, 0.0, 0.0, None);
+    let white = Rgb::new(255.0, 255.0, 255.0, None);
+    let source = move |time: NaiveDateTime| {
+        let color = if time.ordinal() == 2 && time.hour() == 3 {
+            red.clone()
+        } else {
+            white.clone()
+        };
+        (color.clone(), color, None)
+    };
+    let config = Config::new().size_x(2).size_y(2).padding_y(1).build();
+    let mut out = Vec::new();
+    tileline::hourly_year_line(2024, source, &mut out, config).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("<rect").count(), 366 * 24);
+    assert!(out.contains("dominant-baseline=\"hanging\">Feb</text>"));
+    assert!(out.contains("dominant-baseline=\"hanging\">18</text>"));
+    assert!(out.contains("<rect x=\"27\" y=\"29\" rx=\"3\" ry=\"3\" width=\"2\" height=\"2\" style=\"fill:#ff0000"));
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_weeks_line() {
+    use chrono::{Datelike, IsoWeek};
+    use tileline::{ColorScale, WeekDataSource, WeeklyAggregate};
+
+    let aggregate = WeeklyAggregate::new(YearDatasource {}, |days: Vec<DateElement>| {
+        let blue = days.iter().filter(|d| d.get_color().blue() > 0.0).count();
+        let color = ColorScale::blues(7.0).color(blue as f64);
+        (color.clone(), color, None)
+    });
+    let week = chrono::NaiveDate::from_ymd_opt(2023, 5, 1).unwrap().iso_week();
+    assert_eq!(aggregate.get_element(week).get_color().to_hex_string(), "#6baed6");
+
+    let source = |week: IsoWeek| {
+        let color = ColorScale::greens(53.0).color(week.week() as f64);
+        (color.clone(), color, None)
+    };
+    let mut out = Vec::new();
+    tileline::weeks_line(2019..=2021, source, &mut out, Config::new().build()).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/weeks_line.svg").unwrap().to_vec());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_life_weeks() {
+    use chrono::NaiveDate;
+    use std::cell::RefCell;
+    use tileline::{LifeColors, LifeDataSource, LifeWeek, WeekState};
+
+    let start = NaiveDate::from_ymd_opt(2000, 2, 29).unwrap();
+    let today = NaiveDate::from_ymd_opt(2005, 6, 15).unwrap();
+    let weeks = RefCell::new(Vec::new());
+    let source = |week: &LifeWeek| {
+        weeks.borrow_mut().push(week.clone());
+        LifeColors::default().get_element(week)
+    };
+    let mut out = Vec::new();
+    tileline::life_weeks(start, 12, today, source, &mut out, Config::new().build()).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/life_weeks.svg").unwrap().to_vec());
+
+    let weeks = weeks.into_inner();
+    assert_eq!(weeks.len(), 12 * 52);
+    let mut out = Vec::new();
+    assert!(tileline::life_weeks(
+        start,
+        400_000_000,
+        today,
+        LifeColors::default(),
+        &mut out,
+        Config::new().build()
+    )
+    .is_err());
+    let current = weeks
+        .iter()
+        .filter(|w| w.state() == WeekState::Current)
+        .collect::<Vec<_>>();
+    assert_eq!(current.len(), 1);
+    assert_eq!((current[0].age(), current[0].week()), (5, 15));
+    assert!(current[0].start() <= today && today <= current[0].end());
+    // the last week of the year reaches the next anniversary
+    assert_eq!(weeks[51].end(), NaiveDate::from_ymd_opt(2001, 2, 27).unwrap());
+    assert_eq!(weeks[52].start(), NaiveDate::from_ymd_opt(2001, 2, 28).unwrap());
+    assert_eq!(
+        weeks.iter().filter(|w| w.state() == WeekState::Past).count(),
+        5 * 52 + 15
+    );
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_iso_weeks() {
+    use tileline::{WeekLabels, YearOptions};
+
+    let options = YearOptions::new().iso_weeks(true).week_labels(WeekLabels::Both).build();
+    let mut out = Vec::new();
+    tileline::year_line_with_options(2020, YearDatasource {}, &mut out, Config::new().build(), options).unwrap();
+    assert_eq!(
+        out,
+        std::fs::read("./fixtures/year_line_iso_weeks.svg").unwrap().to_vec()
+    );
+
+    // ISO 2021 starts on Monday 4th of January and ends on Sunday 2nd of January 2022
+    let options = YearOptions::new()
+        .iso_weeks(true)
+        .week_labels(WeekLabels::Weeks)
+        .build();
+    let mut out = Vec::new();
+    tileline::year_line_with_options(2021, YearDatasource {}, &mut out, Config::new().build(), options).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("<rect").count(), 52 * 7);
+    assert!(out.contains("xlink:href=\"2021-01-04\""));
+    assert!(out.contains("xlink:href=\"2022-01-02\""));
+    assert!(!out.contains("xlink:href=\"2021-01-03\""));
+    assert!(out.contains(">52</text>"));
+    assert!(!out.contains(">Jan</text>"));
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_month_grid_highlights() {
+    use chrono::NaiveDate;
+    use tileline::{ical_dates, DayOptions};
+
+    let ical = "BEGIN:VCALENDAR\r\nBEGIN:VTIMEZONE\r\nBEGIN:STANDARD\r\nDTSTART:19701025T030000\r\nEND:STANDARD\r\n\
+                END:VTIMEZONE\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20230501\r\nSUMMARY:Labour Day\r\nEND:VEVENT\r\n\
+                BEGIN:VEVENT\r\nDTSTART;TZID=Europe/Rome:20230525T\r\n 090000\r\nEND:VEVENT\r\nEND:VCALENDAR\r\n";
+    let holidays = ical_dates(ical).unwrap();
+    let day = |d| NaiveDate::from_ymd_opt(2023, 5, d).unwrap();
+    assert_eq!(holidays.iter().cloned().collect::<Vec<_>>(), vec![day(1), day(25)]);
+    assert!(ical_dates("BEGIN:VEVENT\nDTSTART:2023\nEND:VEVENT").is_err());
+
+    let options = DayOptions::new()
+        .today(day(17))
+        .holidays(holidays)
+        .weekend_color(Rgb::new(200.0, 200.0, 200.0, None))
+        .holiday_color(Rgb::new(255.0, 0.0, 0.0, None))
+        .today_outline(Rgb::new(0.0, 0.0, 255.0, None))
+        .future_color(Rgb::new(240.0, 240.0, 240.0, None))
+        .build();
+    let mut out = Vec::new();
+    tileline::month_grid_with_options(2023, 5, YearDatasource {}, &mut out, Config::new().build(), options).unwrap();
+    assert_eq!(
+        out,
+        std::fs::read("./fixtures/month_grid_highlights.svg").unwrap().to_vec()
+    );
+    // the 1st is a holiday with data, it keeps its color and gets a red outline
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains(
+        r#"<a xlink:href="2023-05-01" xlink:title="bbb"><rect x="20" y="20" rx="3" ry="3" width="15" height="15" style="fill:#006400;stroke-width:1;stroke:#006400"/>"#
+    ));
+    assert!(out.contains(
+        r#"<path d="M17.5 17.5 L17.5 37.5 L37.5 37.5 L37.5 17.5 Z" style="fill:none;stroke-width:1;stroke:#ff0000"/>"#
+    ));
+    // each weekend day gets its own outline
+    assert_eq!(out.matches("stroke:#c8c8c8").count(), 8);
+    // today keeps its color, the next day is mixed with the grey
+    assert!(out.contains(r#"<rect x="60" y="60" rx="3" ry="3" width="15" height="15" style="fill:#006400"#));
+    assert!(out.contains(r#"<rect x="80" y="60" rx="3" ry="3" width="15" height="15" style="fill:#7878aa"#));
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_date_stats() {
+    use chrono::NaiveDate;
+    use tileline::{ColorScale, DateStats, EventCounts, YearOptions};
+
+    let day = |d| NaiveDate::from_ymd_opt(2023, 5, d).unwrap();
+    let events = vec![day(2), day(3), day(3), day(4), day(8), day(9), day(9), day(9), day(13)];
+    let counts = EventCounts::new(events.clone(), ColorScale::greens(3.0));
+    let stats = DateStats::new(&counts, day(1), day(14));
+    assert_eq!(stats.total(), 9.0);
+    assert_eq!(stats.active_days(), 6);
+    assert_eq!(stats.longest_streak(), 3);
+    // the last day is not active yet, the streak ending the day before is still current
+    assert_eq!(stats.current_streak(), 1);
+    assert_eq!(stats.busiest_day(), Some((day(9), 3.0)));
+    assert_eq!(DateStats::new(&counts, day(1), day(15)).current_streak(), 0);
+    assert_eq!(DateStats::new(&counts, day(8), day(9)).current_streak(), 2);
+    assert_eq!(
+        stats.summary(),
+        "Total: 9\nLongest streak: 3 days\nCurrent streak: 1 day\nBusiest day: 2023-05-09 (3)"
+    );
+
+    let options = YearOptions::new().stats_footer(true).build();
+    let mut out = Vec::new();
+    tileline::date_range_line_with_options(day(1), day(31), counts, &mut out, Config::new().build(), options).unwrap();
+    assert_eq!(
+        out,
+        std::fs::read("./fixtures/date_range_line_stats.svg").unwrap().to_vec()
+    );
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_stats_footer_below_days() {
+    use chrono::NaiveDate;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use tileline::{ColorScale, DateDataSource, Element, EventCounts, YearOptions};
+
+    struct CountingSource<D>(D, Rc<Cell<u32>>);
+    impl<E: Element, D: DateDataSource<E>> DateDataSource<E> for CountingSource<D> {
+        fn get_element(&self, date: NaiveDate) -> E {
+            self.1.set(self.1.get() + 1);
+            self.0.get_element(date)
+        }
+    }
+    // position of the text element holding `text`
+    let text_position = |svg: &str, text: &str| -> (f64, f64) {
+        let start = svg[..svg.find(text).unwrap()].rfind("<text ").unwrap();
+        let attributes = svg[start..].split('"').collect::<Vec<_>>();
+        (attributes[1].parse().unwrap(), attributes[3].parse().unwrap())
+    };
+
+    let day = |d| NaiveDate::from_ymd_opt(2023, 5, d).unwrap();
+    let lookups = Rc::new(Cell::new(0));
+    let counts = CountingSource(
+        EventCounts::new(vec![day(2), day(3)], ColorScale::greens(2.0)),
+        lookups.clone(),
+    );
+    let options = YearOptions::new().stats_footer(true).build();
+    let mut out = Vec::new();
+    tileline::date_range_line_with_options(day(1), day(31), counts, &mut out, Config::new().build(), options).unwrap();
+    // the statistics come from the values drawn, each day is looked up once
+    assert_eq!(lookups.get(), 31);
+    let out = String::from_utf8(out).unwrap();
+    // under the days in the default mode, the last row starts at 140, rather than after the
+    // last week
+    assert_eq!(text_position(&out, "Total: 2"), (20.0, 160.0));
+
+    let options = YearOptions::new().stats_footer(true).build();
+    let counts = EventCounts::new(vec![day(2)], ColorScale::greens(2.0));
+    let mut out = Vec::new();
+    tileline::years_line_with_options(2022..=2023, counts, &mut out, Config::new().build(), options).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    // the next year starts after the four lines of the footer of the previous one
+    let (_, footer) = text_position(&out, "Total: 0");
+    let (_, title) = text_position(&out, ">2023</text>");
+    assert!(title >= footer + 4.0 * 16.0);
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_link_templates() {
+    use chrono::NaiveDate;
+    use tileline::{ColorScale, DayOptions, EventCounts, YearOptions};
+
+    let day = |d| NaiveDate::from_ymd_opt(2023, 5, d).unwrap();
+    let days = DayOptions::new()
+        .link_template("https://ci/runs?date={yyyy-mm-dd}".to_owned())
+        .title_template("{weekday}, {date}: {value}".to_owned())
+        .build();
+    let options = YearOptions::new().days(days).build();
+    let counts = EventCounts::new(vec![day(2), day(2)], ColorScale::greens(2.0));
+    let mut out = Vec::new();
+    tileline::date_range_line_with_options(day(1), day(3), counts, &mut out, Config::new().build(), options).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("<a xlink:href").count(), 3);
+    assert!(out.contains("<a xlink:href=\"https://ci/runs?date=2023-05-02\" xlink:title=\"Tuesday, 05/02/2023: 2\">"));
+
+    // the links of the data source are kept
+    let days = DayOptions::new()
+        .link_template("https://ci/{yyyy-mm-dd}".to_owned())
+        .build();
+    let options = YearOptions::new().days(days).build();
+    let mut out = Vec::new();
+    tileline::date_range_line_with_options(
+        day(1),
+        day(3),
+        YearDatasource {},
+        &mut out,
+        Config::new().build(),
+        options,
+    )
+    .unwrap();
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.contains("<a xlink:href=\"2023-05-01\" xlink:title=\"bbb\">"));
+    assert!(!out.contains("https://ci/"));
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_multi_year_line() {
+    use chrono::{Datelike, NaiveDate};
+    use tileline::{ColorScale, EventCounts, MultiDateDataSource};
+
+    let days = |step: usize| {
+        NaiveDate::from_ymd_opt(2023, 1, 1)
+            .unwrap()
+            .iter_days()
+            .take_while(|d| d.year() == 2023)
+            .step_by(step)
+            .collect::<Vec<_>>()
+    };
+    let series = vec![
+        ("Run".to_owned(), EventCounts::new(days(2), ColorScale::greens(1.0))),
+        ("Read".to_owned(), EventCounts::new(days(3), ColorScale::blues(1.0))),
+    ];
+    assert_eq!(series.labels(), vec!["Run".to_owned(), "Read".to_owned()]);
+    let mut out = Vec::new();
+    tileline::multi_year_line(2023, series, &mut out, Config::new().build()).unwrap();
+    assert_eq!(out, std::fs::read("./fixtures/multi_year_line.svg").unwrap().to_vec());
+
+    let empty: Vec<(String, EventCounts<_, ColorScale>)> = Vec::new();
+    assert!(tileline::multi_year_line(2023, empty, Vec::new(), Config::new().build()).is_err());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_multi_year_line_options() {
+    use chrono::NaiveDate;
+    use tileline::{Annotation, Bar, ColorScale, DayOptions, EventCounts, YearOptions};
+
+    let day = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+    let series = || {
+        vec![
+            (
+                "Run".to_owned(),
+                EventCounts::new(vec![day(1, 2)], ColorScale::greens(1.0)),
+            ),
+            (
+                "Read".to_owned(),
+                EventCounts::new(vec![day(1, 3)], ColorScale::blues(1.0)),
+            ),
+        ]
+    };
+    let options = || {
+        YearOptions::new()
+            .month_outline(Rgb::new(255.0, 0.0, 0.0, None))
+            .annotations(vec![Annotation::new(day(3, 1), day(3, 10), "Trip")])
+            .days(
+                DayOptions::new()
+                    .today(day(5, 17))
+                    .today_outline(Rgb::new(0.0, 0.0, 255.0, None))
+                    .build(),
+            )
+    };
+    let mut out = Vec::new();
+    tileline::multi_year_line_with_options(2023, series(), &mut out, Config::new().build(), options().build()).unwrap();
+    assert_eq!(
+        out,
+        std::fs::read("./fixtures/multi_year_line_options.svg")
+            .unwrap()
+            .to_vec()
+    );
+    // the outlines are drawn on each series, the annotation once after the last one
+    let out = String::from_utf8(out).unwrap();
+    assert_eq!(out.matches("stroke:#ff0000").count(), 2 * 12);
+    // the first series ends at 155 and the second starts at 170, the outlines stay out of the gap
+    assert!(out.contains(r#"<path d="M34.5 17.5 L134.5 17.5 L134.5 77.5 L114.5 77.5 L114.5 157.5 L34.5 157.5 Z""#));
+    assert!(out.contains(r#"<path d="M34.5 167.5 L134.5 167.5 L134.5 227.5 L114.5 227.5 L114.5 307.5 L34.5 307.5 Z""#));
+    assert_eq!(out.matches("stroke-width:2;stroke:#0000ff").count(), 2);
+    assert_eq!(out.matches(">Trip</text>").count(), 1);
+
+    // the grouping of the caller is kept, the default one separates the series
+    let render = |config: Config| {
+        let mut out = Vec::new();
+        tileline::multi_year_line(2023, series(), &mut out, config).unwrap();
+        out
+    };
+    let default = render(Config::new().build());
+    assert_eq!(default, render(Config::new().second_groups(vec![7]).build()));
+    assert_ne!(default, render(Config::new().second_groups(vec![3]).build()));
+
+    let green = Rgb::new(0.0, 160.0, 0.0, None);
+    let intervals = options()
+        .intervals(vec![Bar::new(day(5, 1), day(5, 3), (green.clone(), green, None))])
+        .build();
+    assert!(
+        tileline::multi_year_line_with_options(2023, series(), Vec::new(), Config::new().build(), intervals).is_err()
+    );
+    let footer = options().stats_footer(true).build();
+    assert!(tileline::multi_year_line_with_options(2023, series(), Vec::new(), Config::new().build(), footer).is_err());
+}
+
+#[test]
+#[cfg(feature = "year_line")]
+fn test_year_line_intervals() {
+    use chrono::NaiveDate;
+    use tileline::{Bar, YearOptions};
+
+    let day = |m, d| NaiveDate::from_ymd_opt(2023, m, d).unwrap();
+    let green = Rgb::new(0.0, 160.0, 0.0, None);
+    let intervals = vec![
+        (day(5, 3), day(5, 18), (green.clone(), green.clone(), None)),
+        (day(6, 1), day(6, 1), (green.clone(), green.clone(), None)),
+        (day(8, 1), day(8, 5), (green.clone(), green, None)),
+    ];
+    let options = YearOptions::new()
+        .intervals(intervals.into_iter().map(Bar::from).collect())
+        .build();
+    let mut out = Vec::new();
+    tileline::date_range_line_with_options(
+        day(5, 1),
+        day(6, 30),
+        YearDatasource {},
+        &mut out,
+        Config::new().build(),
+        options,
+    )
+    .unwrap();
+    assert_eq!(
+        out,
+        std::fs::read("./fixtures/date_range_intervals.svg").unwrap().to_vec()
+    );
+}